@@ -0,0 +1,157 @@
+//! Measures the time taken to render the grayscale frames used to dive into the fractals,
+//! at the default dive dimensions of the generator.
+//!
+//! Run it with `cargo bench --bench dive`.
+
+use std::time::{Duration, Instant};
+
+use frustalz::fractal::{EscapeTime, SmoothIterations};
+use frustalz::image::{produce_image, render_image};
+use frustalz::{BurningShip, BurningShipJulia, Multibrot, MultibrotJulia, Tricorn, TricornJulia};
+use frustalz::{Camera, Fractal, Julia, Mandelbrot};
+use image::{Rgb, RgbImage};
+use num_complex::Complex64;
+
+const DIMENSIONS: (u32, u32) = (500, 500);
+const RUNS: u32 = 10;
+
+/// The Mandelbrot fractal iterated without any of the shortcuts,
+/// used as the reference to compare the other timings to.
+struct NaiveMandelbrot {
+    max_iterations: u32,
+}
+
+impl Fractal for NaiveMandelbrot {
+    fn iterations(&self, x: f64, y: f64) -> EscapeTime {
+        let c = Complex64::new(x, y);
+        let mut z = c;
+        let mut iterations = 0;
+        while z.norm_sqr() <= 4.0 && iterations < self.max_iterations {
+            z = z * z + c;
+            iterations += 1;
+        }
+        EscapeTime { iterations, max_iterations: self.max_iterations }
+    }
+
+    fn smooth_iterations(&self, x: f64, y: f64) -> SmoothIterations {
+        let escape = self.iterations(x, y);
+        SmoothIterations { value: escape.ratio(), escaped: escape.escaped() }
+    }
+
+    fn max_iterations(&self) -> u32 {
+        self.max_iterations
+    }
+
+    fn set_max_iterations(&mut self, max_iterations: u32) {
+        self.max_iterations = max_iterations;
+    }
+}
+
+/// How the frames are rendered.
+#[derive(Copy, Clone)]
+enum Path {
+    /// One point at a time.
+    Scalar,
+    /// Using the SIMD batches, the default of `produce_image`.
+    Batch,
+}
+
+fn grayscale<F: Fractal + Sync>(fractal: &F, camera: &Camera, path: Path) -> RgbImage {
+    let painter = |e: EscapeTime| Rgb { data: [e.to_u8(); 3] };
+    match path {
+        Path::Scalar => {
+            render_image(camera, DIMENSIONS, None, |x, y| painter(fractal.iterations(x, y)))
+        }
+        Path::Batch => produce_image(fractal, camera, DIMENSIONS, None, painter),
+    }
+}
+
+/// Renders the frame multiple times and prints the mean time it took,
+/// returns the frame to compare it with the other ones.
+fn bench<F: Fractal + Sync>(name: &str, fractal: &F, camera: &Camera) -> RgbImage {
+    bench_path(name, fractal, camera, Path::Batch)
+}
+
+fn bench_path<F: Fractal + Sync>(name: &str, fractal: &F, camera: &Camera, path: Path) -> RgbImage {
+    let mut image = grayscale(fractal, camera, path);
+
+    let mut total = Duration::new(0, 0);
+    for _ in 0..RUNS {
+        let start = Instant::now();
+        image = grayscale(fractal, camera, path);
+        total += start.elapsed();
+    }
+
+    let mean = total / RUNS;
+    let millis = mean.as_secs() as f64 * 1e3 + mean.subsec_nanos() as f64 / 1e6;
+    println!("{:<48} {:>10.2} ms", name, millis);
+
+    image
+}
+
+fn assert_same(reference: &RgbImage, image: &RgbImage) {
+    assert!(reference.as_ref() == image.as_ref(), "shortcuts must not change the rendered frame");
+}
+
+fn main() {
+    let (width, height) = DIMENSIONS;
+
+    // the first frame of the dive, a frame around the period-2 bulb and one
+    // around the period-3 bulb, where the points inside the fractal dominate
+    let mut cameras = vec![("whole", Camera::new([width as f64, height as f64]))];
+    for &(view, point, zoom) in &[("neck", [-0.75, 0.0], 0.5), ("bulb", [-0.122, 0.745], 0.1)] {
+        let mut camera = Camera::new([width as f64, height as f64]);
+        camera.target_on_world(point, zoom);
+        cameras.push((view, camera));
+    }
+
+    for (view, camera) in &cameras {
+        let naive = NaiveMandelbrot { max_iterations: 1000 };
+        let reference = bench(&format!("mandelbrot {} naive", view), &naive, camera);
+
+        let mut mandelbrot = Mandelbrot::new();
+        mandelbrot.set_max_iterations(1000);
+        let name = format!("mandelbrot {} scalar", view);
+        let image = bench_path(&name, &mandelbrot, camera, Path::Scalar);
+        assert_same(&reference, &image);
+
+        let image = bench(&format!("mandelbrot {} cardioid and bulb", view), &mandelbrot, camera);
+        assert_same(&reference, &image);
+
+        mandelbrot.set_periodicity_checking(true);
+        let image = bench(&format!("mandelbrot {} with periodicity", view), &mandelbrot, camera);
+        assert_same(&reference, &image);
+    }
+
+    let camera = &cameras[0].1;
+    let mut julia = Julia::new(-0.123, 0.745);
+    julia.set_max_iterations(1000);
+    let reference = bench_path("julia whole scalar", &julia, camera, Path::Scalar);
+
+    let image = bench("julia whole", &julia, camera);
+    assert_same(&reference, &image);
+
+    julia.set_periodicity_checking(true);
+    let image = bench("julia whole with periodicity", &julia, camera);
+    assert_same(&reference, &image);
+
+    // the fractals iterated point by point, the periodicity checking speeds up the views
+    // with points inside the fractal, the tricorn julia view has none and is slowed down,
+    // the generator only enables it when a tenth of the points are inside
+    let fractals: Vec<(&str, Box<dyn Fractal + Sync>)> = vec![
+        ("burning ship", Box::new(BurningShip::new())),
+        ("tricorn", Box::new(Tricorn::new())),
+        ("multibrot", Box::new(Multibrot::new(3))),
+        ("burning ship julia", Box::new(BurningShipJulia::new(-0.5, -0.5))),
+        ("tricorn julia", Box::new(TricornJulia::new(-0.1, 0.7))),
+        ("multibrot julia", Box::new(MultibrotJulia::new(-0.5, 0.5, 3))),
+    ];
+    for (name, mut fractal) in fractals {
+        fractal.set_max_iterations(1000);
+        let reference = bench(&format!("{} whole", name), &fractal, camera);
+
+        fractal.set_periodicity_checking(true);
+        let image = bench(&format!("{} whole with periodicity", name), &fractal, camera);
+        assert_same(&reference, &image);
+    }
+}
@@ -0,0 +1,119 @@
+use deflate::deflate_bytes_zlib;
+use image::RgbImage;
+
+use crate::animation::Looping;
+
+const SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// Encodes the frames as an animated PNG, the colors are kept intact,
+/// the viewers that do not support the animation show the first frame.
+///
+/// The frames must all have the same dimensions, `delay` is the time
+/// every frame is shown in milliseconds.
+pub fn encode_apng(frames: &[RgbImage], delay: u16, looping: Looping) -> Vec<u8> {
+    assert!(!frames.is_empty(), "an animation needs at least one frame");
+    let (width, height) = frames[0].dimensions();
+
+    let mut out = SIGNATURE.to_vec();
+
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&width.to_be_bytes());
+    header.extend_from_slice(&height.to_be_bytes());
+    // 8 bits RGB, deflate, adaptive filtering, no interlacing
+    header.extend_from_slice(&[8, 2, 0, 0, 0]);
+    write_chunk(&mut out, b"IHDR", &header);
+
+    let plays = match looping {
+        Looping::Forever => 0,
+        Looping::Times(times) => times as u32,
+    };
+    let mut control = Vec::with_capacity(8);
+    control.extend_from_slice(&(frames.len() as u32).to_be_bytes());
+    control.extend_from_slice(&plays.to_be_bytes());
+    write_chunk(&mut out, b"acTL", &control);
+
+    // the frame controls and the frame data share the sequence numbers
+    let mut sequence = 0u32;
+    for (i, frame) in frames.iter().enumerate() {
+        assert!(frame.dimensions() == (width, height), "frames must have the same dimensions");
+
+        let mut control = Vec::with_capacity(26);
+        control.extend_from_slice(&sequence.to_be_bytes());
+        control.extend_from_slice(&width.to_be_bytes());
+        control.extend_from_slice(&height.to_be_bytes());
+        control.extend_from_slice(&[0; 8]);
+        control.extend_from_slice(&delay.to_be_bytes());
+        control.extend_from_slice(&1000u16.to_be_bytes());
+        // the frames are not disposed and replace the previous ones
+        control.extend_from_slice(&[0, 0]);
+        write_chunk(&mut out, b"fcTL", &control);
+        sequence += 1;
+
+        let data = deflate_bytes_zlib(&filter(frame));
+        if i == 0 {
+            write_chunk(&mut out, b"IDAT", &data);
+        } else {
+            let mut frame_data = Vec::with_capacity(data.len() + 4);
+            frame_data.extend_from_slice(&sequence.to_be_bytes());
+            frame_data.extend_from_slice(&data);
+            write_chunk(&mut out, b"fdAT", &frame_data);
+            sequence += 1;
+        }
+    }
+
+    write_chunk(&mut out, b"IEND", &[]);
+    out
+}
+
+fn write_chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = out.len();
+    out.extend_from_slice(kind);
+    out.extend_from_slice(data);
+    let crc = crc32(&out[start..]);
+    out.extend_from_slice(&crc.to_be_bytes());
+}
+
+/// Prefixes every row with the Paeth filter, the bytes are predicted from
+/// the left, the upper and the upper left pixels which suits smooth gradients.
+fn filter(frame: &RgbImage) -> Vec<u8> {
+    let stride = frame.width() as usize * 3;
+    let raw: &[u8] = frame;
+
+    let mut filtered = Vec::with_capacity((stride + 1) * frame.height() as usize);
+    for (y, row) in raw.chunks(stride).enumerate() {
+        filtered.push(4);
+        for (x, &byte) in row.iter().enumerate() {
+            let left = if x >= 3 { row[x - 3] } else { 0 };
+            let up = if y > 0 { raw[(y - 1) * stride + x] } else { 0 };
+            let up_left = if x >= 3 && y > 0 { raw[(y - 1) * stride + x - 3] } else { 0 };
+            filtered.push(byte.wrapping_sub(paeth(left, up, up_left)));
+        }
+    }
+    filtered
+}
+
+fn paeth(a: u8, b: u8, c: u8) -> u8 {
+    let p = a as i16 + b as i16 - c as i16;
+    let (pa, pb, pc) = ((p - a as i16).abs(), (p - b as i16).abs(), (p - c as i16).abs());
+    if pa <= pb && pa <= pc {
+        a
+    } else if pb <= pc {
+        b
+    } else {
+        c
+    }
+}
+
+/// The CRC-32 of the PNG chunks.
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xedb8_8320 & mask);
+        }
+    }
+    !crc
+}
@@ -0,0 +1,121 @@
+use crate::camera::Camera;
+
+/// A camera the path goes through at a given time.
+#[derive(Debug, Clone)]
+pub struct Keyframe {
+    /// The time of the keyframe in seconds.
+    pub time: f64,
+    pub camera: Camera,
+}
+
+/// The moves of a camera going through keyframes.
+///
+/// The zoom is interpolated exponentially, every second zooms by the same factor
+/// between two keyframes. The center follows a Catmull-Rom spline that goes through
+/// the centers of the keyframes, it moves along with the zoom so that the point
+/// zoomed into stays at the same place on screen, like `Camera::target_on_offset` does.
+#[derive(Debug, Clone, Default)]
+pub struct CameraPath {
+    keyframes: Vec<Keyframe>,
+}
+
+impl CameraPath {
+    pub fn new() -> CameraPath {
+        CameraPath { keyframes: Vec::new() }
+    }
+
+    /// Adds a keyframe, the keyframes must be added in chronological order.
+    pub fn keyframe(&mut self, time: f64, camera: &Camera) -> &mut Self {
+        assert!(time.is_finite(), "keyframe time must be finite");
+        assert!(camera.zoom > 0.0, "keyframe zoom must be positive");
+        if let Some(last) = self.keyframes.last() {
+            assert!(time > last.time, "keyframes must be added in chronological order");
+        }
+        self.keyframes.push(Keyframe { time, camera: camera.clone() });
+        self
+    }
+
+    pub fn keyframes(&self) -> &[Keyframe] {
+        &self.keyframes
+    }
+
+    /// The time between the first and the last keyframes.
+    pub fn duration(&self) -> f64 {
+        match (self.keyframes.first(), self.keyframes.last()) {
+            (Some(first), Some(last)) => last.time - first.time,
+            _ => 0.0,
+        }
+    }
+
+    /// Returns the camera at the given time, the time is clamped to the keyframes.
+    ///
+    /// Panics if the path has no keyframe.
+    pub fn camera_at(&self, time: f64) -> Camera {
+        let keyframes = &self.keyframes;
+        assert!(!keyframes.is_empty(), "camera path must have keyframes");
+
+        let last = keyframes.len() - 1;
+        if last == 0 || time <= keyframes[0].time {
+            return keyframes[0].camera.clone();
+        }
+        if time >= keyframes[last].time {
+            return keyframes[last].camera.clone();
+        }
+
+        let i = keyframes.iter().rposition(|k| k.time <= time).unwrap_or(0);
+        let (start, end) = (&keyframes[i], &keyframes[i + 1]);
+        let s = (time - start.time) / (end.time - start.time);
+        let (from, to) = (&start.camera, &end.camera);
+
+        let zoom = from.zoom.powf(1.0 - s) * to.zoom.powf(s);
+
+        // the center covers the distance in proportion of the zoom done,
+        // or of the time when the keyframes have the same zoom
+        let u = if (from.zoom - to.zoom).abs() > 1e-9 * from.zoom.max(to.zoom) {
+            (from.zoom - zoom) / (from.zoom - to.zoom)
+        } else {
+            s
+        };
+
+        // the offsets keep the precision of the centers whatever the zoom is
+        let origin = &from.center;
+        let offset = |k: usize| keyframes[k].camera.center.offset_from(origin);
+        let points = [offset(i.max(1) - 1), [0.0, 0.0], offset(i + 1), offset((i + 2).min(last))];
+        let center = origin.offset(catmull_rom(points, u));
+
+        let lerp = |a: f64, b: f64| a + (b - a) * s;
+        let rotation = lerp(from.rotation, to.rotation);
+        let transform = match (from.transform, to.transform) {
+            (Some([[a, b], [c, d]]), Some([[e, f], [g, h]])) => {
+                Some([[lerp(a, e), lerp(b, f)], [lerp(c, g), lerp(d, h)]])
+            }
+            (transform, _) => transform,
+        };
+
+        Camera { screen_size: from.screen_size, center, zoom, rotation, transform }
+    }
+}
+
+/// Interpolates between the second and the third points of a Catmull-Rom spline,
+/// the tangents are scaled to the length of the segment, the far keyframes
+/// of the start of a dive would otherwise throw the center off the deep ones.
+fn catmull_rom(points: [[f64; 2]; 4], t: f64) -> [f64; 2] {
+    let [p0, p1, p2, p3] = points;
+    let distance = |a: [f64; 2], b: [f64; 2]| (a[0] - b[0]).hypot(a[1] - b[1]);
+    let (d0, d1, d2) = (distance(p0, p1), distance(p1, p2), distance(p2, p3));
+    let w1 = if d0 + d1 > 0.0 { d1 / (d0 + d1) } else { 0.0 };
+    let w2 = if d1 + d2 > 0.0 { d1 / (d1 + d2) } else { 0.0 };
+
+    // the cubic Hermite basis
+    let (t2, t3) = (t * t, t * t * t);
+    let h00 = 2.0 * t3 - 3.0 * t2 + 1.0;
+    let h10 = t3 - 2.0 * t2 + t;
+    let h01 = 3.0 * t2 - 2.0 * t3;
+    let h11 = t3 - t2;
+
+    let coordinate = |i: usize| {
+        let (m1, m2) = ((p2[i] - p0[i]) * w1, (p3[i] - p1[i]) * w2);
+        h00 * p1[i] + h10 * m1 + h01 * p2[i] + h11 * m2
+    };
+    [coordinate(0), coordinate(1)]
+}
@@ -0,0 +1,164 @@
+use image::RgbImage;
+
+/// The maximum number of colors of an indexed image.
+pub const MAX_COLORS: usize = 256;
+
+/// The colors are counted on 5 bits per channel.
+const BITS: u32 = 5;
+const SIDE: usize = 1 << BITS;
+
+/// A table of at most 256 colors shared by all the frames of an animation,
+/// a shared table does not flicker from a frame to the other.
+#[derive(Debug, Clone)]
+pub struct ColorTable {
+    colors: Vec<[u8; 3]>,
+    /// The index of the nearest color of every 5 bits color.
+    lookup: Vec<u8>,
+}
+
+#[derive(Debug, Copy, Clone, Default)]
+struct Bucket {
+    count: u64,
+    sums: [u64; 3],
+}
+
+impl ColorTable {
+    /// Builds the color table of the frames using the median cut algorithm:
+    /// the box of colors of the widest range is cut in two at its median
+    /// until there is as many boxes as colors.
+    pub fn median_cut(frames: &[RgbImage], max_colors: usize) -> ColorTable {
+        assert!(
+            max_colors > 0 && max_colors <= MAX_COLORS,
+            "color table must have 1 to 256 colors"
+        );
+
+        let mut buckets = vec![Bucket::default(); SIDE * SIDE * SIDE];
+        for frame in frames {
+            for pixel in frame.pixels() {
+                let bucket = &mut buckets[reduce(pixel.data)];
+                bucket.count += 1;
+                for (sum, &c) in bucket.sums.iter_mut().zip(pixel.data.iter()) {
+                    *sum += c as u64;
+                }
+            }
+        }
+
+        let used: Vec<_> = (0..buckets.len()).filter(|&i| buckets[i].count > 0).collect();
+        let mut boxes = if used.is_empty() { Vec::new() } else { vec![used] };
+
+        while boxes.len() < max_colors {
+            // the box with the widest range of a channel is cut
+            let widest = boxes
+                .iter()
+                .enumerate()
+                .map(|(i, b)| (i, widest_channel(b)))
+                .filter(|&(_, (_, range))| range > 0)
+                .max_by_key(|&(_, (_, range))| range);
+
+            let (i, (channel, _)) = match widest {
+                Some(widest) => widest,
+                None => break,
+            };
+
+            let mut cut = boxes.swap_remove(i);
+            cut.sort_by_key(|&index| expand(index)[channel]);
+
+            // the median of the pixels, not of the colors
+            let total: u64 = cut.iter().map(|&index| buckets[index].count).sum();
+            let mut seen = 0;
+            let mut median = cut.len() - 1;
+            for (j, &index) in cut.iter().enumerate() {
+                seen += buckets[index].count;
+                if seen * 2 >= total {
+                    median = j;
+                    break;
+                }
+            }
+            let median = median.clamp(0, cut.len() - 2);
+
+            let upper = cut.split_off(median + 1);
+            boxes.push(cut);
+            boxes.push(upper);
+        }
+
+        let colors: Vec<_> = boxes
+            .iter()
+            .map(|b| {
+                let count: u64 = b.iter().map(|&i| buckets[i].count).sum();
+                let mut color = [0; 3];
+                for (channel, c) in color.iter_mut().enumerate() {
+                    let sum: u64 = b.iter().map(|&i| buckets[i].sums[channel]).sum();
+                    *c = (sum / count.max(1)) as u8;
+                }
+                color
+            })
+            .collect();
+
+        ColorTable::new(if colors.is_empty() { vec![[0; 3]] } else { colors })
+    }
+
+    /// Creates a color table of the given colors.
+    pub fn new(colors: Vec<[u8; 3]>) -> ColorTable {
+        assert!(
+            !colors.is_empty() && colors.len() <= MAX_COLORS,
+            "color table must have 1 to 256 colors"
+        );
+
+        let lookup = (0..SIDE * SIDE * SIDE)
+            .map(|index| {
+                let [r, g, b] = expand(index);
+                let distance = |c: &[u8; 3]| {
+                    let d = |a: u8, b: u8| (a as i32 - b as i32) * (a as i32 - b as i32);
+                    d(c[0], r) + d(c[1], g) + d(c[2], b)
+                };
+                let nearest = colors.iter().enumerate().min_by_key(|&(_, c)| distance(c));
+                nearest.map_or(0, |(i, _)| i as u8)
+            })
+            .collect();
+
+        ColorTable { colors, lookup }
+    }
+
+    pub fn colors(&self) -> &[[u8; 3]] {
+        &self.colors
+    }
+
+    /// Returns the index of the color of the table nearest to the given color.
+    pub fn index_of(&self, color: [u8; 3]) -> u8 {
+        self.lookup[reduce(color)]
+    }
+
+    /// Returns the color indices of the pixels of the frame, row by row.
+    pub fn indices(&self, frame: &RgbImage) -> Vec<u8> {
+        frame.pixels().map(|p| self.index_of(p.data)).collect()
+    }
+}
+
+/// Returns the index of the 5 bits bucket of the color.
+fn reduce(color: [u8; 3]) -> usize {
+    let [r, g, b] = color;
+    let shift = 8 - BITS;
+    ((r as usize >> shift) * SIDE + (g as usize >> shift)) * SIDE + (b as usize >> shift)
+}
+
+/// Returns the color at the center of the 5 bits bucket.
+fn expand(index: usize) -> [u8; 3] {
+    let shift = 8 - BITS;
+    let half = 1 << (shift - 1);
+    let channel = |c: usize| ((c % SIDE) << shift | half) as u8;
+    [channel(index / (SIDE * SIDE)), channel(index / SIDE), channel(index)]
+}
+
+/// Returns the channel of the widest range of the box along with this range.
+fn widest_channel(colors: &[usize]) -> (usize, u8) {
+    let mut widest = (0, 0);
+    for channel in 0..3 {
+        let values = colors.iter().map(|&i| expand(i)[channel]);
+        let (min, max) = values.fold((u8::MAX, 0), |(min, max), c| (min.min(c), max.max(c)));
+        let range = max.saturating_sub(min);
+        if range > widest.1 {
+            widest = (channel, range);
+        }
+    }
+    widest
+}
@@ -0,0 +1,157 @@
+use std::str::FromStr;
+
+use image::{imageops, FilterType, RgbImage};
+
+use crate::animation::{encode_apng, encode_gif};
+
+/// The longest delay between two frames that still looks fluid, in milliseconds,
+/// the frames are dropped to fit in a size budget until this delay is reached.
+const MAX_FLUID_DELAY: u32 = 100;
+
+/// The frames are not shrunk to fit in a size budget under this size.
+const MIN_DIMENSION: u32 = 32;
+
+/// The image formats that support animations.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AnimationFormat {
+    /// Widely supported but limited to 256 colors.
+    Gif,
+    /// Keeps the colors intact, viewers that do not support it show the first frame.
+    Apng,
+}
+
+impl FromStr for AnimationFormat {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "gif" => Ok(AnimationFormat::Gif),
+            "apng" => Ok(AnimationFormat::Apng),
+            _ => Err("animation format must be \"gif\" or \"apng\""),
+        }
+    }
+}
+
+/// How many times an animation plays.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Looping {
+    Forever,
+    Times(u16),
+}
+
+impl FromStr for Looping {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "forever" => Ok(Looping::Forever),
+            _ => match s.parse() {
+                Ok(0) | Err(_) => Err("loops must be \"forever\" or a number between 1 and 65535"),
+                Ok(times) => Ok(Looping::Times(times)),
+            },
+        }
+    }
+}
+
+/// Encodes frames as an animated image.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct AnimationEncoder {
+    format: AnimationFormat,
+    /// The time every frame is shown in milliseconds.
+    delay: u32,
+    looping: Looping,
+    ping_pong: bool,
+    size_budget: Option<usize>,
+}
+
+impl AnimationEncoder {
+    /// An encoder showing 25 frames per second that loops forever.
+    pub fn new(format: AnimationFormat) -> AnimationEncoder {
+        AnimationEncoder {
+            format,
+            delay: 40,
+            looping: Looping::Forever,
+            ping_pong: false,
+            size_budget: None,
+        }
+    }
+
+    /// The time every frame is shown in milliseconds,
+    /// GIF rounds it to hundredths of a second.
+    pub fn delay(&mut self, delay: u32) -> &mut Self {
+        assert!(delay > 0 && delay <= u16::MAX as u32, "frame delay must be in ]0, 65535] ms");
+        self.delay = delay;
+        self
+    }
+
+    pub fn looping(&mut self, looping: Looping) -> &mut Self {
+        assert!(looping != Looping::Times(0), "an animation must play at least once");
+        self.looping = looping;
+        self
+    }
+
+    /// Plays the frames forward then backward, the loop has no visible seam.
+    pub fn ping_pong(&mut self, ping_pong: bool) -> &mut Self {
+        self.ping_pong = ping_pong;
+        self
+    }
+
+    /// The maximum size of the encoded animation in bytes, the frames are dropped,
+    /// keeping the same duration, then shrunk until the animation fits.
+    pub fn size_budget(&mut self, size_budget: Option<usize>) -> &mut Self {
+        self.size_budget = size_budget;
+        self
+    }
+
+    pub fn format(&self) -> AnimationFormat {
+        self.format
+    }
+
+    /// Encodes the frames, they must all have the same dimensions.
+    ///
+    /// Returns an error if there is no frame or if the animation can not fit in the size budget.
+    pub fn encode(&self, frames: &[RgbImage]) -> Result<Vec<u8>, &'static str> {
+        if frames.is_empty() {
+            return Err("an animation needs at least one frame");
+        }
+
+        let mut frames = frames.to_vec();
+        if self.ping_pong && frames.len() > 2 {
+            let backward: Vec<_> = frames[1..frames.len() - 1].iter().rev().cloned().collect();
+            frames.extend(backward);
+        }
+
+        let mut delay = self.delay;
+        loop {
+            let data = match self.format {
+                AnimationFormat::Gif => {
+                    let hundredths = ((delay + 5) / 10).clamp(2, u16::MAX as u32);
+                    encode_gif(&frames, hundredths as u16, self.looping)
+                }
+                AnimationFormat::Apng => {
+                    encode_apng(&frames, delay.min(u16::MAX as u32) as u16, self.looping)
+                }
+            };
+
+            match self.size_budget {
+                Some(budget) if data.len() > budget => (),
+                _ => return Ok(data),
+            }
+
+            if frames.len() > 1 && delay * 2 <= MAX_FLUID_DELAY {
+                frames = frames.into_iter().step_by(2).collect();
+                delay *= 2;
+            } else {
+                let (width, height) = frames[0].dimensions();
+                let (width, height) = (width * 3 / 4, height * 3 / 4);
+                if width < MIN_DIMENSION || height < MIN_DIMENSION {
+                    return Err("the animation does not fit in the size budget");
+                }
+                frames = frames
+                    .iter()
+                    .map(|f| imageops::resize(f, width, height, FilterType::Triangle))
+                    .collect();
+            }
+        }
+    }
+}
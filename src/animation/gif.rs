@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+
+use image::RgbImage;
+
+use crate::animation::{ColorTable, Looping, MAX_COLORS};
+
+/// The codes of the LZW compression are at most 12 bits long.
+const MAX_CODE_BITS: u32 = 12;
+const MAX_CODES: u16 = 1 << MAX_CODE_BITS;
+
+/// Encodes the frames as an animated GIF, the colors are reduced
+/// to a table of 256 colors shared by all the frames.
+///
+/// The frames must all have the same dimensions, `delay` is the time
+/// every frame is shown in hundredths of a second.
+pub fn encode_gif(frames: &[RgbImage], delay: u16, looping: Looping) -> Vec<u8> {
+    assert!(!frames.is_empty(), "an animation needs at least one frame");
+    let (width, height) = frames[0].dimensions();
+    assert!(
+        width <= u16::MAX as u32 && height <= u16::MAX as u32,
+        "gif dimensions must fit in 16 bits"
+    );
+
+    let table = ColorTable::median_cut(frames, MAX_COLORS);
+
+    let mut out = Vec::new();
+    out.extend_from_slice(b"GIF89a");
+
+    // the logical screen with a global color table of 256 colors
+    push_u16(&mut out, width as u16);
+    push_u16(&mut out, height as u16);
+    out.extend_from_slice(&[0xf7, 0, 0]);
+    for i in 0..MAX_COLORS {
+        let color = table.colors().get(i).cloned().unwrap_or([0; 3]);
+        out.extend_from_slice(&color);
+    }
+
+    // the netscape extension tells how many times the animation plays
+    let loops = match looping {
+        Looping::Forever => 0,
+        Looping::Times(times) => times.saturating_sub(1),
+    };
+    if looping != Looping::Times(1) {
+        out.extend_from_slice(b"\x21\xff\x0bNETSCAPE2.0\x03\x01");
+        push_u16(&mut out, loops);
+        out.push(0);
+    }
+
+    for frame in frames {
+        assert!(frame.dimensions() == (width, height), "frames must have the same dimensions");
+
+        // the graphic control extension gives the delay, the frames are not disposed
+        out.extend_from_slice(&[0x21, 0xf9, 0x04, 0x04]);
+        push_u16(&mut out, delay);
+        out.extend_from_slice(&[0, 0]);
+
+        // the image descriptor, covering the whole screen without local color table
+        out.push(0x2c);
+        push_u16(&mut out, 0);
+        push_u16(&mut out, 0);
+        push_u16(&mut out, width as u16);
+        push_u16(&mut out, height as u16);
+        out.push(0);
+
+        out.push(8);
+        let data = lzw_compress(&table.indices(frame));
+        for block in data.chunks(255) {
+            out.push(block.len() as u8);
+            out.extend_from_slice(block);
+        }
+        out.push(0);
+    }
+
+    out.push(0x3b);
+    out
+}
+
+fn push_u16(out: &mut Vec<u8>, value: u16) {
+    out.extend_from_slice(&[value as u8, (value >> 8) as u8]);
+}
+
+/// Writes the codes of variable width, least significant bits first.
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u32,
+    bits: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, code: u16, width: u32) {
+        self.buffer |= (code as u32) << self.bits;
+        self.bits += width;
+        while self.bits >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.bits -= 8;
+        }
+    }
+
+    /// Writes a code of the LZW compression, `next` is the code of the next string.
+    ///
+    /// The width grows as soon as the decoder can receive codes that do not fit,
+    /// the decoder adds its strings one code later than the encoder.
+    fn emit(&mut self, code: u16, next: u16, width: &mut u32) {
+        self.write(code, *width);
+        if next >= 1 << *width && *width < MAX_CODE_BITS {
+            *width += 1;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bits > 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+        self.bytes
+    }
+}
+
+/// Compresses the 8 bits color indices using the variable width LZW of the GIF format,
+/// the table of strings is cleared when it is full.
+fn lzw_compress(indices: &[u8]) -> Vec<u8> {
+    let clear = 256u16;
+    let end = 257u16;
+
+    let mut writer = BitWriter { bytes: Vec::new(), buffer: 0, bits: 0 };
+    let mut strings = HashMap::new();
+    let mut next = end + 1;
+    let mut width = 9;
+
+    writer.emit(clear, next, &mut width);
+
+    let mut prefix = match indices.first() {
+        Some(&index) => index as u16,
+        None => {
+            writer.emit(end, next, &mut width);
+            return writer.finish();
+        }
+    };
+
+    for &index in &indices[1..] {
+        if let Some(&code) = strings.get(&(prefix, index)) {
+            prefix = code;
+            continue;
+        }
+
+        writer.emit(prefix, next, &mut width);
+        if next < MAX_CODES {
+            strings.insert((prefix, index), next);
+            next += 1;
+        } else {
+            writer.emit(clear, next, &mut width);
+            strings.clear();
+            next = end + 1;
+            width = 9;
+        }
+        prefix = index as u16;
+    }
+
+    writer.emit(prefix, next, &mut width);
+    writer.emit(end, next, &mut width);
+    writer.finish()
+}
@@ -0,0 +1,58 @@
+use image::{Rgb, RgbImage};
+use num_complex::Complex64;
+use palette::Gradient;
+
+use crate::animation::Video;
+use crate::camera::Camera;
+use crate::fractal::{EscapeTime, Fractal, Julia};
+use crate::image::{produce_image, ComplexPalette, SubGradient};
+
+/// Sweeps the constant `c` of a Julia fractal along a path while the camera stays still,
+/// every frame shows the Julia set of the next constant.
+#[derive(Debug, Clone)]
+pub struct JuliaMorph {
+    path: Gradient<ComplexPalette>,
+}
+
+impl JuliaMorph {
+    pub fn new(path: Gradient<ComplexPalette>) -> JuliaMorph {
+        JuliaMorph { path }
+    }
+
+    /// One of the hand-picked sweeps of `SubGradient::julia_presets`.
+    pub fn preset(index: usize) -> Option<JuliaMorph> {
+        SubGradient::julia_presets().get(index).map(|s| JuliaMorph::new(s.gradient()))
+    }
+
+    /// Returns the constant of every frame, evenly spread along the path,
+    /// the first and the last frames show its ends.
+    pub fn constants(&self, count: u32) -> impl Iterator<Item = Complex64> + '_ {
+        let (start, end) = self.path.domain();
+        (0..count).map(move |i| {
+            let t = i as f64 / (count - 1).max(1) as f64;
+            let ComplexPalette(c) = self.path.get(start + t * (end - start));
+            c
+        })
+    }
+
+    /// Renders every frame of the video with the camera, the camera
+    /// takes the dimensions of the video, each frame is rendered when it is iterated.
+    pub fn render<'a, C>(
+        &'a self,
+        camera: &Camera,
+        video: &Video,
+        painter: C,
+    ) -> impl Iterator<Item = RgbImage> + 'a
+    where
+        C: Fn(EscapeTime) -> Rgb<u8> + Sync + Send + 'a,
+    {
+        let (width, height) = video.dimensions();
+        let camera = Camera { screen_size: [width as f64, height as f64], ..camera.clone() };
+
+        self.constants(video.frame_count()).map(move |c| {
+            let mut fractal = Julia::new(c.re, c.im);
+            fractal.set_periodicity_checking(true);
+            produce_image(&fractal, &camera, (width, height), None, &painter)
+        })
+    }
+}
@@ -0,0 +1,15 @@
+mod apng;
+mod camera_path;
+mod color_table;
+mod encoder;
+mod gif;
+mod julia_morph;
+mod video;
+
+pub use self::apng::encode_apng;
+pub use self::camera_path::{CameraPath, Keyframe};
+pub use self::color_table::{ColorTable, MAX_COLORS};
+pub use self::encoder::{AnimationEncoder, AnimationFormat, Looping};
+pub use self::gif::encode_gif;
+pub use self::julia_morph::JuliaMorph;
+pub use self::video::{write_png_sequence, write_y4m, Video};
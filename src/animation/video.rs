@@ -0,0 +1,126 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+use image::RgbImage;
+
+use crate::animation::CameraPath;
+use crate::camera::Camera;
+
+/// The frame rate and the duration of an animation, the camera path
+/// is stretched over the duration whatever the times of its keyframes are.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Video {
+    dimensions: (u32, u32),
+    frame_rate: u32,
+    /// The duration in seconds.
+    duration: f64,
+}
+
+impl Video {
+    /// A ten seconds video at 25 frames per second.
+    pub fn new(dimensions: (u32, u32)) -> Video {
+        let (width, height) = dimensions;
+        assert!(width > 0 && height > 0, "video dimensions can not be zero");
+        Video { dimensions, frame_rate: 25, duration: 10.0 }
+    }
+
+    pub fn frame_rate(&mut self, frame_rate: u32) -> &mut Self {
+        assert!(frame_rate > 0, "frame rate must be positive");
+        self.frame_rate = frame_rate;
+        self
+    }
+
+    pub fn duration(&mut self, duration: f64) -> &mut Self {
+        assert!(duration > 0.0, "video duration must be positive");
+        self.duration = duration;
+        self
+    }
+
+    pub fn dimensions(&self) -> (u32, u32) {
+        self.dimensions
+    }
+
+    pub fn frames_per_second(&self) -> u32 {
+        self.frame_rate
+    }
+
+    /// The number of frames of the video, at least one.
+    pub fn frame_count(&self) -> u32 {
+        ((self.duration * self.frame_rate as f64).round() as u32).max(1)
+    }
+
+    /// Returns the camera of every frame, the first frame shows the first keyframe
+    /// and the last frame the last keyframe.
+    pub fn cameras<'a>(&self, path: &'a CameraPath) -> impl Iterator<Item = Camera> + 'a {
+        let count = self.frame_count();
+        let (width, height) = self.dimensions;
+        let start = path.keyframes().first().map_or(0.0, |k| k.time);
+        let duration = path.duration();
+
+        (0..count).map(move |i| {
+            let t = if count > 1 { i as f64 / (count - 1) as f64 } else { 0.0 };
+            let camera = path.camera_at(start + t * duration);
+            Camera { screen_size: [width as f64, height as f64], ..camera }
+        })
+    }
+}
+
+/// Saves the frames as numbered PNG images in the directory, `frame-00000.png` first,
+/// the directory is created if needed.
+pub fn write_png_sequence<I>(frames: I, directory: &Path) -> io::Result<()>
+where
+    I: IntoIterator<Item = RgbImage>,
+{
+    fs::create_dir_all(directory)?;
+    for (i, frame) in frames.into_iter().enumerate() {
+        frame.save(directory.join(format!("frame-{:05}.png", i)))?;
+    }
+    Ok(())
+}
+
+/// Writes the frames as a YUV4MPEG2 stream, the uncompressed format that video
+/// encoders like `ffmpeg` read, the chroma is not subsampled (`C444`).
+///
+/// The frames must all have the same dimensions.
+pub fn write_y4m<W, I>(frames: I, frame_rate: u32, mut writer: W) -> io::Result<()>
+where
+    W: Write,
+    I: IntoIterator<Item = RgbImage>,
+{
+    let mut dimensions = None;
+    for frame in frames {
+        match dimensions {
+            None => {
+                let (width, height) = frame.dimensions();
+                writeln!(
+                    writer,
+                    "YUV4MPEG2 W{} H{} F{}:1 Ip A1:1 C444",
+                    width, height, frame_rate
+                )?;
+                dimensions = Some((width, height));
+            }
+            Some(dimensions) => {
+                assert!(frame.dimensions() == dimensions, "frames must have the same dimensions")
+            }
+        }
+
+        // the planes are written one after the other, using the BT.601 studio range
+        let len = (frame.width() * frame.height()) as usize;
+        let mut planes = vec![0u8; len * 3];
+        for (i, pixel) in frame.pixels().enumerate() {
+            let [r, g, b] = pixel.data;
+            let (r, g, b) = (r as f32, g as f32, b as f32);
+            let y = 16.0 + 0.257 * r + 0.504 * g + 0.098 * b;
+            let u = 128.0 - 0.148 * r - 0.291 * g + 0.439 * b;
+            let v = 128.0 + 0.439 * r - 0.368 * g - 0.071 * b;
+            planes[i] = y.round() as u8;
+            planes[len + i] = u.round() as u8;
+            planes[2 * len + i] = v.round() as u8;
+        }
+
+        writer.write_all(b"FRAME\n")?;
+        writer.write_all(&planes)?;
+    }
+    writer.flush()
+}
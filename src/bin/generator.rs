@@ -1,10 +1,19 @@
 use std::collections::hash_map::DefaultHasher;
+use std::fs::{self, File};
 use std::hash::{Hash, Hasher};
+use std::io::{self, BufWriter};
+use std::path::{Path, PathBuf};
 
 use frustalz::{
-    generate::{DateSeed, Generator},
+    animation::{
+        write_png_sequence, write_y4m, AnimationEncoder, AnimationFormat, JuliaMorph, Looping,
+        Video,
+    },
+    generate::{julia_morph_frames, DateSeed, Generator},
     image::{Antialiazing, ScreenDimensions},
+    Formula,
 };
+use image::RgbImage;
 use rand::{SeedableRng, StdRng};
 use structopt::StructOpt;
 
@@ -29,11 +38,111 @@ pub struct Settings {
     /// Whether the program produce all images while diving in the fractal
     #[structopt(long = "no-debug-images")]
     pub no_debug_images: bool,
+
+    /// Allows diving much deeper into the mandelbrot fractal, takes more time
+    #[structopt(long = "deep-zoom")]
+    pub deep_zoom: bool,
+
+    /// Generates the images of the first versions for the same seed, ignored with a formula
+    #[structopt(long = "legacy-bailout")]
+    pub legacy_bailout: bool,
+
+    /// Dives into the fractal of this formula, like "z^3 + c*sin(z)"
+    #[structopt(long = "formula")]
+    pub formula: Option<Formula>,
+
+    /// Uses the formula with a constant c, like the julia fractals do
+    #[structopt(long = "formula-julia")]
+    pub formula_julia: bool,
+
+    /// Renders a video of the dive, a y4m stream or an animated image if the path
+    /// ends with ".y4m", ".gif" or ".apng", a directory of numbered PNG images otherwise
+    #[structopt(long = "video", parse(from_os_str))]
+    pub video: Option<PathBuf>,
+
+    /// Dimensions of the frames of the video, the dive dimensions by default
+    #[structopt(long = "video-dimensions")]
+    pub video_dimensions: Option<ScreenDimensions>,
+
+    /// Number of frames per second of the video
+    #[structopt(long = "video-frame-rate", default_value = "25")]
+    pub video_frame_rate: u32,
+
+    /// Duration of the video in seconds
+    #[structopt(long = "video-duration", default_value = "10")]
+    pub video_duration: f64,
+
+    /// Plays the animated images forward then backward
+    #[structopt(long = "ping-pong")]
+    pub ping_pong: bool,
+
+    /// Number of times the animated images play, "forever" or a number
+    #[structopt(long = "loops", default_value = "forever")]
+    pub loops: Looping,
+
+    /// Renders the julia fractals of one of the preset constant sweeps (0 to 7) instead of
+    /// diving, the frames are saved like the video, to "./julia-morph" by default
+    #[structopt(long = "julia-morph")]
+    pub julia_morph: Option<usize>,
+}
+
+/// Writes the frames one by one as they are rendered, except for the animated images
+/// that are encoded once all the frames are known.
+fn save_video<I>(frames: I, path: &Path, settings: &Settings) -> io::Result<()>
+where
+    I: IntoIterator<Item = RgbImage>,
+{
+    let frame_rate = settings.video_frame_rate;
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+    if extension == "y4m" {
+        File::create(path).and_then(|file| write_y4m(frames, frame_rate, BufWriter::new(file)))
+    } else if let Ok(format) = extension.parse::<AnimationFormat>() {
+        let mut encoder = AnimationEncoder::new(format);
+        encoder
+            .delay((1000 / frame_rate).max(1))
+            .ping_pong(settings.ping_pong)
+            .looping(settings.loops);
+        let frames: Vec<_> = frames.into_iter().collect();
+        encoder
+            .encode(&frames)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))
+            .and_then(|data| fs::write(path, data))
+    } else {
+        write_png_sequence(frames, path)
+    }
+}
+
+fn video(settings: &Settings) -> Video {
+    let dims = settings
+        .video_dimensions
+        .or(settings.dive_dimensions)
+        .unwrap_or(ScreenDimensions(500, 500));
+    let mut video = Video::new(dims.as_tuple());
+    video.frame_rate(settings.video_frame_rate).duration(settings.video_duration);
+    video
 }
 
 fn main() {
     let settings = Settings::from_args();
 
+    if let Some(index) = settings.julia_morph {
+        let morph = match JuliaMorph::preset(index) {
+            Some(morph) => morph,
+            None => {
+                eprintln!("julia morph preset must be between 0 and 7");
+                return;
+            }
+        };
+
+        let frames = julia_morph_frames(&morph, &video(&settings));
+        let path = settings.video.clone().unwrap_or_else(|| PathBuf::from("./julia-morph"));
+        match save_video(frames, &path, &settings) {
+            Ok(_) => println!("julia morph saved to {:?}", path),
+            Err(e) => eprintln!("can not save julia morph to {:?}: {}", path, e),
+        }
+        return;
+    }
+
     let rng = {
         let datetime = settings.date_seed.unwrap_or_default();
         println!("{:?}", datetime);
@@ -57,8 +166,17 @@ fn main() {
         generator.antialiazing(anti);
     }
     generator.debug_images(!settings.no_debug_images);
+    generator.deep_zoom(settings.deep_zoom);
+    generator.legacy_bailout(settings.legacy_bailout);
+    if let Some(formula) = settings.formula.clone() {
+        generator.formula(formula);
+    }
+    generator.formula_julia(settings.formula_julia);
+    if settings.video.is_some() {
+        generator.video(video(&settings));
+    }
 
-    let (info, image) = generator.generate();
+    let (info, image, frames) = generator.generate_with_frames();
 
     println!("{}", info);
 
@@ -66,4 +184,11 @@ fn main() {
         Ok(_) => println!("image saved to \"./image.png\""),
         Err(e) => eprintln!("can not save image to \"./image.png\": {}", e),
     }
+
+    if let Some(path) = &settings.video {
+        match save_video(frames, path, &settings) {
+            Ok(_) => println!("video saved to {:?}", path),
+            Err(e) => eprintln!("can not save video to {:?}: {}", path, e),
+        }
+    }
 }
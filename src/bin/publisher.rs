@@ -1,4 +1,5 @@
 use std::collections::hash_map::DefaultHasher;
+use std::fs;
 use std::hash::{Hash, Hasher};
 use std::io::BufWriter;
 
@@ -8,6 +9,7 @@ use egg_mode::{
     KeyPair, Token,
 };
 use frustalz::{
+    animation::{AnimationEncoder, AnimationFormat, Looping, Video},
     generate::{DateSeed, Generator},
     image::{Antialiazing, ScreenDimensions},
 };
@@ -17,6 +19,9 @@ use rand::{SeedableRng, StdRng};
 use structopt::StructOpt;
 use tokio_core::reactor;
 
+/// The maximum number of characters of a tweet.
+const TWEET_MAX_CHARS: usize = 280;
+
 #[derive(Debug, Clone, StructOpt)]
 pub struct Settings {
     /// The date to use as a seed rounded to the hour
@@ -46,6 +51,42 @@ pub struct Settings {
     /// Whether the program produce all images while diving in the fractal
     #[structopt(long = "no-debug-images")]
     pub no_debug_images: bool,
+
+    /// Allows diving much deeper into the mandelbrot fractal, takes more time
+    #[structopt(long = "deep-zoom")]
+    pub deep_zoom: bool,
+
+    /// Generates the images of the first versions for the same seed, ignored with a formula
+    #[structopt(long = "legacy-bailout")]
+    pub legacy_bailout: bool,
+
+    /// Uploads an animated GIF of the dive instead of the final image
+    #[structopt(long = "animation")]
+    pub animation: bool,
+
+    /// Dimensions of the frames of the animation, the dive dimensions by default
+    #[structopt(long = "animation-dimensions")]
+    pub animation_dimensions: Option<ScreenDimensions>,
+
+    /// Number of frames per second of the animation
+    #[structopt(long = "animation-frame-rate", default_value = "15")]
+    pub animation_frame_rate: u32,
+
+    /// Duration of the animation in seconds
+    #[structopt(long = "animation-duration", default_value = "6")]
+    pub animation_duration: f64,
+
+    /// Plays the animation forward then backward
+    #[structopt(long = "ping-pong")]
+    pub ping_pong: bool,
+
+    /// Number of times the animation plays, "forever" or a number
+    #[structopt(long = "loops", default_value = "forever")]
+    pub loops: Looping,
+
+    /// Maximum size of the animation in bytes, the upload limit of the animated GIFs
+    #[structopt(long = "size-budget", default_value = "15000000")]
+    pub size_budget: usize,
 }
 
 fn image_to_png(image: RgbImage) -> Vec<u8> {
@@ -107,27 +148,73 @@ fn main() {
             generator.antialiazing(anti);
         }
         generator.debug_images(!settings.no_debug_images);
+        generator.deep_zoom(settings.deep_zoom);
+        generator.legacy_bailout(settings.legacy_bailout);
+
+        if settings.animation {
+            let dims = settings.animation_dimensions.or(settings.dive_dimensions);
+            let mut video = Video::new(dims.unwrap_or(ScreenDimensions(500, 500)).as_tuple());
+            video.frame_rate(settings.animation_frame_rate).duration(settings.animation_duration);
+            generator.video(video);
+        }
 
-        let (info, image) = generator.generate();
+        let (info, image, frames) = generator.generate_with_frames();
+
+        println!("{}", info);
+
+        // the encoder needs all the frames at once to fit the size budget
+        let frames: Vec<_> = frames.collect();
+
+        // the fractals that are not dived into have no frame, the still image is uploaded
+        let animation = if frames.is_empty() {
+            None
+        } else {
+            // the animated PNGs are shown as still images once uploaded, only GIFs play
+            let mut encoder = AnimationEncoder::new(AnimationFormat::Gif);
+            encoder
+                .delay((1000 / settings.animation_frame_rate).max(1))
+                .looping(settings.loops)
+                .ping_pong(settings.ping_pong)
+                .size_budget(Some(settings.size_budget));
+            match encoder.encode(&frames) {
+                Ok(data) => Some(data),
+                Err(e) => {
+                    eprintln!("can not encode the animation, uploading the image: {}", e);
+                    None
+                }
+            }
+        };
 
         if settings.save_image || settings.dry_run {
             match image.save("./image.png") {
                 Ok(_) => println!("image saved to \"./image.png\""),
                 Err(e) => eprintln!("can not save image to \"./image.png\": {}", e),
             }
+            if let Some(data) = &animation {
+                match fs::write("./animation.gif", data) {
+                    Ok(_) => println!("animation saved to \"./animation.gif\""),
+                    Err(e) => eprintln!("can not save animation to \"./animation.gif\": {}", e),
+                }
+            }
         }
 
         if !settings.dry_run {
-            let image = image_to_png(image);
-            let builder = UploadBuilder::new(image, media_types::image_png());
+            let builder = match animation {
+                Some(data) => UploadBuilder::new(data, media_types::image_gif()),
+                None => UploadBuilder::new(image_to_png(image), media_types::image_png()),
+            };
             let media_handle = core.run(builder.call(&token, &handle)).unwrap();
 
-            let message = info.to_string();
+            // the position of deep zooms is printed in full but shortened in the tweet
+            let message = info.message(TWEET_MAX_CHARS);
             let draft = DraftTweet::new(message).media_ids(&[media_handle.id]);
-            let tweet = core.run(draft.send(&token, &handle)).unwrap();
-
-            if let Some(url) = tweet.entities.urls.first().map(|u| &u.url) {
-                println!("tweet url: {}", url);
+            match core.run(draft.send(&token, &handle)) {
+                Ok(tweet) => {
+                    if let Some(url) = tweet.entities.urls.first().map(|u| &u.url) {
+                        println!("tweet url: {}", url);
+                    }
+                }
+                Err(e) => eprintln!("can not send the tweet: {}", e),
             }
         }
     }
@@ -1,13 +1,37 @@
+use crate::precision::PrecisePoint;
+
+/// The view on the world, its center is stored in high precision
+/// and the renderers are given `f64` offsets relative to it,
+/// this way the precision of the points is kept down to zooms around `1e-60`.
+///
+/// The center saturates instead of panicking when it is moved beyond `2^32` from the origin.
 #[derive(Debug, Clone)]
 pub struct Camera {
     pub screen_size: [f64; 2],
-    pub center: [f64; 2],
+    pub center: PrecisePoint,
     pub zoom: f64,
+    /// The rotation of the view around the center in degrees,
+    /// the horizontal of the screen follows this angle in the world.
+    pub rotation: f64,
+    /// A linear map applied to the view before the rotation, it can stretch
+    /// or shear the world seen on screen, it must be invertible.
+    pub transform: Option<[[f64; 2]; 2]>,
 }
 
 impl Camera {
     pub fn new(screen_size: [f64; 2]) -> Camera {
-        Camera { screen_size, center: [0.0, 0.0], zoom: 1.0 }
+        Camera {
+            screen_size,
+            center: PrecisePoint::from_f64([0.0, 0.0]),
+            zoom: 1.0,
+            rotation: 0.0,
+            transform: None,
+        }
+    }
+
+    /// Returns the center rounded to `f64` coordinates.
+    pub fn center_f64(&self) -> [f64; 2] {
+        self.center.to_f64()
     }
 
     /// Moves the camera center in order to
@@ -21,41 +45,149 @@ impl Camera {
     ///
     /// `point` is the point in screen coordinates (`[0, width[`).
     pub fn target_on_screen(&mut self, point: [f64; 2], zoom: f64) {
-        let point = self.screen_to_world(point);
-        self.target_on_world(point, zoom)
+        let offset = self.screen_to_offset(point);
+        self.target_on_offset(offset, zoom)
     }
 
     pub fn target_on_world(&mut self, point: [f64; 2], zoom: f64) {
-        let [cx, cy] = self.center;
+        let [cx, cy] = self.center_f64();
         let [x, y] = point;
+        self.target_on_offset([x - cx, y - cy], zoom)
+    }
+
+    /// Same as `target_on_world` but the point is given in high precision.
+    pub fn target_on_precise(&mut self, point: &PrecisePoint, zoom: f64) {
+        let offset = point.offset_from(&self.center);
+        self.target_on_offset(offset, zoom)
+    }
+
+    /// Same as `target_on_world` but the point is given as an offset from the current center,
+    /// the precision of the center is kept whatever the zoom is.
+    pub fn target_on_offset(&mut self, offset: [f64; 2], zoom: f64) {
+        let [ox, oy] = offset;
 
         // https://stackoverflow.com/a/22700814/1941280
         //
         // move the center keeping the screen distance the same between
         // the center and the targeted point:
         //
-        // `cx - x` translate the point to the center
-        // `* zoom` reduce this to the current zoom
-        // `+ x` translate it back to the original position w/o zoom
-        let cx = (cx - x) * zoom + x;
-        let cy = (cy - y) * zoom + y;
+        // `(cx - x) * zoom + x` is the new center, from the old center it is
+        // `(x - cx) * (1 - zoom)` away, which is the offset reduced to the current zoom
+        let movement = [ox * (1.0 - zoom), oy * (1.0 - zoom)];
 
         self.zoom = zoom;
-        self.center = [cx, cy];
+        self.center = self.center.offset(movement);
     }
 
     /// Transforms the point in screen coordinates in a point in world coordinates,
     /// taking the screen data and the zoom into account.
     ///
+    /// The world coordinates are rounded to `f64`, prefer `screen_to_offset`
+    /// when the precision of deep zooms is required.
+    ///
     /// `point` is the point in screen coordinates (`[0, width[`).
     pub fn screen_to_world(&self, point: [f64; 2]) -> [f64; 2] {
+        let [cx, cy] = self.center_f64();
+        let [x, y] = self.screen_to_offset(point);
+
+        [x + cx, y + cy]
+    }
+
+    /// Same as `screen_to_world` but returns `None` when the point is outside the screen.
+    pub fn checked_screen_to_world(&self, point: [f64; 2]) -> Option<[f64; 2]> {
+        if !self.on_screen(point) {
+            return None;
+        }
+        Some(self.screen_to_world(point))
+    }
+
+    /// Transforms the point in screen coordinates in an offset from the camera center
+    /// in world coordinates, the offset keeps its precision at any zoom.
+    ///
+    /// `point` is the point in screen coordinates (`[0, width[`).
+    pub fn screen_to_offset(&self, point: [f64; 2]) -> [f64; 2] {
         let [sx, sy] = self.screen_size;
-        let [cx, cy] = self.center;
         let [x, y] = point;
 
         assert!(x >= 0.0 && x < sx, "x is outside screen domain coordinates");
         assert!(y >= 0.0 && y < sy, "y is outside screen domain coordinates");
 
+        self.unclipped_screen_to_offset(point)
+    }
+
+    /// Transforms the point in world coordinates in a point in screen coordinates,
+    /// the inverse of `screen_to_world`.
+    ///
+    /// Returns `None` when the point is not visible on screen.
+    pub fn world_to_screen(&self, point: [f64; 2]) -> Option<[f64; 2]> {
+        let [cx, cy] = self.center_f64();
+        let [x, y] = point;
+        self.offset_to_screen([x - cx, y - cy])
+    }
+
+    /// Same as `world_to_screen` but the point is given as an offset from the camera center,
+    /// the inverse of `screen_to_offset`.
+    pub fn offset_to_screen(&self, offset: [f64; 2]) -> Option<[f64; 2]> {
+        let point = self.unclipped_offset_to_screen(offset);
+        if self.on_screen(point) {
+            Some(point)
+        } else {
+            None
+        }
+    }
+
+    /// Same as `world_to_screen` but also transforms the points outside the screen,
+    /// useful to draw the shapes that are only partly visible.
+    ///
+    /// The coordinates are not finite if the transform of the view is not invertible.
+    pub fn unclipped_world_to_screen(&self, point: [f64; 2]) -> [f64; 2] {
+        let [cx, cy] = self.center_f64();
+        let [x, y] = point;
+        self.unclipped_offset_to_screen([x - cx, y - cy])
+    }
+
+    /// Returns the top left and the bottom right corners of the smallest
+    /// rectangle of the world that contains everything visible on screen.
+    pub fn visible_world(&self) -> ([f64; 2], [f64; 2]) {
+        let [cx, cy] = self.center_f64();
+        let [sx, sy] = self.screen_size;
+
+        // the view can be rotated, every corner of the screen is needed
+        let corners = [[0.0, 0.0], [sx, 0.0], [0.0, sy], [sx, sy]];
+        let offsets = corners.iter().map(|&c| self.unclipped_screen_to_offset(c));
+        let ([left, top], [right, bottom]) = offsets.fold(
+            ([f64::INFINITY; 2], [f64::NEG_INFINITY; 2]),
+            |([left, top], [right, bottom]), [x, y]| {
+                ([left.min(x), top.min(y)], [right.max(x), bottom.max(y)])
+            },
+        );
+
+        ([left + cx, top + cy], [right + cx, bottom + cy])
+    }
+
+    /// Whether the point in world coordinates is visible on screen.
+    pub fn is_visible(&self, point: [f64; 2]) -> bool {
+        self.world_to_screen(point).is_some()
+    }
+
+    /// Returns the linear map from the view to the world offsets,
+    /// the rotation applied after the transform.
+    pub fn view_matrix(&self) -> [[f64; 2]; 2] {
+        let [[a, b], [c, d]] = self.transform.unwrap_or([[1.0, 0.0], [0.0, 1.0]]);
+        let (sin, cos) = self.rotation.to_radians().sin_cos();
+        [[cos * a - sin * c, cos * b - sin * d], [sin * a + cos * c, sin * b + cos * d]]
+    }
+
+    fn on_screen(&self, point: [f64; 2]) -> bool {
+        let [sx, sy] = self.screen_size;
+        let [x, y] = point;
+        x >= 0.0 && x < sx && y >= 0.0 && y < sy
+    }
+
+    fn unclipped_screen_to_offset(&self, point: [f64; 2]) -> [f64; 2] {
+        let [sx, sy] = self.screen_size;
+        let [x, y] = point;
+
         let screen_ratio = sx / sy;
 
         // reduce the point to world coordinates:
@@ -65,10 +197,25 @@ impl Camera {
         // `- 1.0` move to `[-1, 1[`
         // `* screen_ratio` keep the screen ratio
         // `* self.zoom` reduce to the current zoom
-        // `+ cx` translate the point to the center
-        let x = (x * 2.0 / sx - 1.0) * screen_ratio * self.zoom + cx;
-        let y = (y * 2.0 / sy - 1.0) * self.zoom + cy;
+        let x = (x * 2.0 / sx - 1.0) * screen_ratio * self.zoom;
+        let y = (y * 2.0 / sy - 1.0) * self.zoom;
+
+        // then rotate and transform the view
+        let [[a, b], [c, d]] = self.view_matrix();
+        [a * x + b * y, c * x + d * y]
+    }
+
+    fn unclipped_offset_to_screen(&self, offset: [f64; 2]) -> [f64; 2] {
+        let [sx, sy] = self.screen_size;
+        let [x, y] = offset;
+
+        // undo the rotation and the transform of the view
+        let [[a, b], [c, d]] = self.view_matrix();
+        let det = a * d - b * c;
+        let (x, y) = ((d * x - b * y) / det, (a * y - c * x) / det);
 
-        [x, y]
+        // a world unit is this many pixels in both directions
+        let scale = sy / (2.0 * self.zoom);
+        [x * scale + sx / 2.0, y * scale + sy / 2.0]
     }
 }
@@ -0,0 +1,142 @@
+use std::f64::consts::SQRT_2;
+
+use num_complex::Complex64;
+
+/// The norm used to measure how far an orbit went from the origin.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Norm {
+    /// The usual modulus `|z|`.
+    Euclidean,
+    /// `|re(z)| + |im(z)|`, produces diamond shaped escape bands.
+    Manhattan,
+    /// `max(|re(z)|, |im(z)|)`, produces square shaped escape bands.
+    Chebyshev,
+}
+
+/// The test that decides whether an orbit is still bounded or escaped.
+#[derive(Debug, Copy, Clone)]
+pub enum Bailout {
+    /// The orbit escapes when its norm gets greater than the radius.
+    Radius { radius: f64, norm: Norm },
+    /// The orbit is bounded while the predicate returns `true`.
+    Custom(fn(Complex64) -> bool),
+    /// The tests used before bailouts were configurable, see `generate::Generator::legacy_bailout`.
+    ///
+    /// `Mandelbrot` tested `(z * z).re <= 4`, `Julia` tested `(z + z).re <= 4`,
+    /// the other fractals tested `|z|² <= 4`.
+    Legacy,
+}
+
+impl Bailout {
+    pub fn radius(radius: f64) -> Bailout {
+        assert!(radius > 0.0, "bailout radius must be positive");
+        Bailout::Radius { radius, norm: Norm::Euclidean }
+    }
+
+    pub fn with_norm(radius: f64, norm: Norm) -> Bailout {
+        assert!(radius > 0.0, "bailout radius must be positive");
+        Bailout::Radius { radius, norm }
+    }
+
+    /// Whether the orbit value `z` is still bounded.
+    pub fn bounded(&self, z: Complex64) -> bool {
+        self.bounded_with_legacy(z, |z| z.norm_sqr() <= 4.0)
+    }
+
+    /// Same as `bounded` but uses the `legacy` test in legacy mode,
+    /// for the fractals that did not use `|z|² <= 4` before bailouts were configurable.
+    pub(crate) fn bounded_with_legacy(&self, z: Complex64, legacy: fn(Complex64) -> bool) -> bool {
+        match *self {
+            Bailout::Radius { radius, norm: Norm::Euclidean } => z.norm_sqr() <= radius * radius,
+            Bailout::Radius { radius, norm: Norm::Manhattan } => z.re.abs() + z.im.abs() <= radius,
+            Bailout::Radius { radius, norm: Norm::Chebyshev } => {
+                z.re.abs().max(z.im.abs()) <= radius
+            }
+            Bailout::Custom(bounded) => bounded(z),
+            Bailout::Legacy => legacy(z),
+        }
+    }
+
+    /// Whether every value inside the disk of the given radius is considered bounded,
+    /// the points known to stay in this disk can then skip the iterations.
+    pub(crate) fn bounds_disk(&self, radius: f64) -> bool {
+        match *self {
+            Bailout::Radius { radius: r, norm: Norm::Euclidean } => r >= radius,
+            Bailout::Radius { radius: r, norm: Norm::Manhattan } => r >= radius * SQRT_2,
+            Bailout::Radius { radius: r, norm: Norm::Chebyshev } => r >= radius,
+            Bailout::Custom(_) => false,
+            // all the legacy tests hold for `|z| <= 2`
+            Bailout::Legacy => radius <= 2.0,
+        }
+    }
+}
+
+impl PartialEq for Bailout {
+    fn eq(&self, other: &Bailout) -> bool {
+        match (*self, *other) {
+            (Bailout::Radius { radius: a, norm: n }, Bailout::Radius { radius: b, norm: m }) => {
+                a == b && n == m
+            }
+            // predicates are compared by address
+            (Bailout::Custom(a), Bailout::Custom(b)) => a as usize == b as usize,
+            (Bailout::Legacy, Bailout::Legacy) => true,
+            _ => false,
+        }
+    }
+}
+
+impl Default for Bailout {
+    /// The escape radius of 2, no orbit that goes past it stays bounded.
+    fn default() -> Bailout {
+        Bailout::radius(2.0)
+    }
+}
+
+/// The test that decides whether an orbit settled on a finite attractor,
+/// used by the fractals that can converge as well as escape.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ConvergenceBailout {
+    /// The orbit converged when two successive values are closer than the tolerance.
+    Successive { tolerance: f64 },
+    /// The orbit converged when it gets closer than the tolerance to the attractor.
+    Attractor { attractor: Complex64, tolerance: f64 },
+}
+
+impl ConvergenceBailout {
+    pub fn successive(tolerance: f64) -> ConvergenceBailout {
+        assert!(tolerance > 0.0, "convergence tolerance must be positive");
+        ConvergenceBailout::Successive { tolerance }
+    }
+
+    pub fn attractor(attractor: Complex64, tolerance: f64) -> ConvergenceBailout {
+        assert!(tolerance > 0.0, "convergence tolerance must be positive");
+        ConvergenceBailout::Attractor { attractor, tolerance }
+    }
+
+    /// Whether the orbit converged, `previous` is the value that preceded `z`.
+    pub fn converged(&self, previous: Complex64, z: Complex64) -> bool {
+        self.distance(previous, z) <= self.tolerance() * self.tolerance()
+    }
+
+    pub fn tolerance(&self) -> f64 {
+        match *self {
+            ConvergenceBailout::Successive { tolerance } => tolerance,
+            ConvergenceBailout::Attractor { tolerance, .. } => tolerance,
+        }
+    }
+
+    /// The squared distance compared to the tolerance.
+    pub(crate) fn distance(&self, previous: Complex64, z: Complex64) -> f64 {
+        match *self {
+            ConvergenceBailout::Successive { .. } => (z - previous).norm_sqr(),
+            ConvergenceBailout::Attractor { attractor, .. } => (z - attractor).norm_sqr(),
+        }
+    }
+}
+
+impl Default for ConvergenceBailout {
+    /// Successive values closer than `1e-6`, whatever the attractor is.
+    fn default() -> ConvergenceBailout {
+        ConvergenceBailout::successive(1e-6)
+    }
+}
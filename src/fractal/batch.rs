@@ -0,0 +1,162 @@
+use wide::{f64x4, CmpEq, CmpLe, CmpLt};
+
+use crate::fractal::{Bailout, EscapeTime, Norm, PERIODICITY_INTERVAL, PERIODICITY_TOLERANCE};
+
+/// The bailout tests the SIMD batches know how to evaluate on lanes.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) enum LaneBailout {
+    /// `|z|² <= radius²`, the radius is given squared.
+    Radius(f64),
+    /// `(z * z).re <= 4`, the legacy test of `Mandelbrot`.
+    LegacySquare,
+    /// `(z + z).re <= 4`, the legacy test of `Julia`.
+    LegacyDouble,
+}
+
+impl LaneBailout {
+    /// Returns the lane test equivalent to the bailout, `legacy` is the test used
+    /// in legacy mode, `None` if the bailout can only be evaluated point by point.
+    pub(crate) fn from_bailout(bailout: &Bailout, legacy: LaneBailout) -> Option<LaneBailout> {
+        match *bailout {
+            Bailout::Radius { radius, norm: Norm::Euclidean } => {
+                Some(LaneBailout::Radius(radius * radius))
+            }
+            Bailout::Legacy => Some(legacy),
+            _ => None,
+        }
+    }
+}
+
+/// The orbits iterated by a batch, `z -> z² + c`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) struct QuadraticBatch {
+    pub max_iterations: u32,
+    pub periodicity_checking: bool,
+    pub bailout: LaneBailout,
+    /// The constant of a Julia set, `None` to use the point itself like the Mandelbrot set.
+    pub julia: Option<[f64; 2]>,
+}
+
+/// The number of points iterated together.
+const LANES: usize = 4;
+
+/// Iterates the lanes and returns their iteration counts,
+/// the `done` lanes are reported as bounded without being iterated.
+///
+/// The kernel mirrors the scalar `escape` function operation by operation,
+/// lanes are frozen once done so that every lane gives the exact scalar result.
+/// All the lanes step together, they share the periodicity bookkeeping
+/// of the Brent's algorithm like they share the iteration count.
+fn quadratic_f64x4(
+    batch: &QuadraticBatch,
+    z: ([f64; LANES], [f64; LANES]),
+    c: ([f64; LANES], [f64; LANES]),
+    done: [bool; LANES],
+) -> [u32; LANES] {
+    let (mut zr, mut zi) = (f64x4::new(z.0), f64x4::new(z.1));
+    let (cr, ci) = (f64x4::new(c.0), f64x4::new(c.1));
+
+    let zero = f64x4::splat(0.0);
+    let one = f64x4::splat(1.0);
+    let four = f64x4::splat(4.0);
+    let max = f64x4::splat(batch.max_iterations as f64);
+    let tolerance = f64x4::splat(PERIODICITY_TOLERANCE);
+
+    let mut flags = [0.0; LANES];
+    for (flag, done) in flags.iter_mut().zip(&done) {
+        *flag = if *done { 1.0 } else { 0.0 };
+    }
+    let done = f64x4::new(flags).cmp_eq(one);
+    let mut iterations = done.blend(max, zero);
+    let mut active = !done;
+
+    let (mut saved_r, mut saved_i) = (zr, zi);
+    let mut power = 1u32;
+    let mut length = 0u32;
+    let mut until_check = PERIODICITY_INTERVAL;
+
+    loop {
+        let bounded = match batch.bailout {
+            LaneBailout::Radius(r2) => (zr * zr + zi * zi).cmp_le(f64x4::splat(r2)),
+            LaneBailout::LegacySquare => (zr * zr - zi * zi).cmp_le(four),
+            LaneBailout::LegacyDouble => (zr + zr).cmp_le(four),
+        };
+        active = active & bounded & iterations.cmp_lt(max);
+        if active.none() {
+            break;
+        }
+
+        let re = zr * zr - zi * zi + cr;
+        let im = zr * zi + zi * zr + ci;
+        zr = active.blend(re, zr);
+        zi = active.blend(im, zi);
+        iterations = active.blend(iterations + one, iterations);
+
+        if batch.periodicity_checking {
+            until_check -= 1;
+            if until_check == 0 {
+                until_check = PERIODICITY_INTERVAL;
+                let (dr, di) = (zr - saved_r, zi - saved_i);
+                let cycle = active & (dr * dr + di * di).cmp_lt(tolerance);
+                iterations = cycle.blend(max, iterations);
+                active &= !cycle;
+            }
+
+            length += 1;
+            if length == power {
+                saved_r = zr;
+                saved_i = zi;
+                power *= 2;
+                length = 0;
+            }
+        }
+    }
+
+    let mut counts = [0; LANES];
+    for (count, iterations) in counts.iter_mut().zip(iterations.to_array().iter()) {
+        *count = *iterations as u32;
+    }
+    counts
+}
+
+/// Evaluates the points by groups of lanes, the last group is padded
+/// with its last point. `skip` tells the points known to never escape.
+pub(crate) fn quadratic_batch<S>(
+    batch: &QuadraticBatch,
+    xs: &[f64],
+    ys: &[f64],
+    escapes: &mut [EscapeTime],
+    skip: S,
+) where
+    S: Fn(f64, f64) -> bool,
+{
+    assert!(
+        xs.len() == escapes.len() && ys.len() == escapes.len(),
+        "batch coordinates and escapes must have the same length"
+    );
+
+    let max_iterations = batch.max_iterations;
+    let julia = batch.julia.map(|[re, im]| ([re; LANES], [im; LANES]));
+
+    for (start, escapes) in (0..xs.len()).step_by(LANES).zip(escapes.chunks_mut(LANES)) {
+        let last = start + escapes.len() - 1;
+        let mut points = ([0.0; LANES], [0.0; LANES]);
+        let mut done = [false; LANES];
+        for (lane, done) in done.iter_mut().enumerate() {
+            let i = (start + lane).min(last);
+            points.0[lane] = xs[i];
+            points.1[lane] = ys[i];
+            *done = skip(xs[i], ys[i]);
+        }
+
+        // the orbits of the Mandelbrot set start on the point
+        let counts = match julia {
+            Some(c) => quadratic_f64x4(batch, points, c, done),
+            None => quadratic_f64x4(batch, points, points, done),
+        };
+
+        for (escape, iterations) in escapes.iter_mut().zip(counts.iter()) {
+            *escape = EscapeTime { iterations: *iterations, max_iterations };
+        }
+    }
+}
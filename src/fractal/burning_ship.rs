@@ -0,0 +1,83 @@
+use crate::fractal::Bailout;
+use crate::fractal::{escape, EscapeTime, Fractal, SmoothIterations, DEFAULT_MAX_ITERATIONS};
+use crate::fractal::{follow_orbit, Orbit};
+use num_complex::Complex64;
+
+/// The Burning Ship fractal, iterates `(|re(z)| + i|im(z)|)² + c`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct BurningShip {
+    max_iterations: u32,
+    periodicity_checking: bool,
+    bailout: Bailout,
+}
+
+impl BurningShip {
+    pub fn new() -> BurningShip {
+        BurningShip {
+            max_iterations: DEFAULT_MAX_ITERATIONS,
+            periodicity_checking: false,
+            bailout: Bailout::default(),
+        }
+    }
+
+    fn escape(&self, x: f64, y: f64) -> (u32, Complex64) {
+        let c = Complex64::new(x, y);
+        escape(
+            c,
+            self.max_iterations,
+            self.periodicity_checking,
+            |z| self.bailout.bounded(z),
+            |z| burning_ship(z) + c,
+        )
+    }
+}
+
+/// Folds `z` into the upper right quadrant before squaring it.
+pub(crate) fn burning_ship(z: Complex64) -> Complex64 {
+    let z = Complex64::new(z.re.abs(), z.im.abs());
+    z * z
+}
+
+impl Default for BurningShip {
+    fn default() -> BurningShip {
+        BurningShip::new()
+    }
+}
+
+impl Fractal for BurningShip {
+    fn iterations(&self, x: f64, y: f64) -> EscapeTime {
+        let (iterations, _) = self.escape(x, y);
+        EscapeTime { iterations, max_iterations: self.max_iterations }
+    }
+
+    fn smooth_iterations(&self, x: f64, y: f64) -> SmoothIterations {
+        let (iterations, z) = self.escape(x, y);
+        SmoothIterations::new(iterations, self.max_iterations, z, 2.0)
+    }
+
+    fn max_iterations(&self) -> u32 {
+        self.max_iterations
+    }
+
+    fn set_max_iterations(&mut self, max_iterations: u32) {
+        self.max_iterations = max_iterations;
+    }
+
+    fn set_periodicity_checking(&mut self, enabled: bool) {
+        self.periodicity_checking = enabled;
+    }
+
+    fn set_bailout(&mut self, bailout: Bailout) {
+        self.bailout = bailout;
+    }
+
+    fn orbit(&self, x: f64, y: f64) -> Option<Orbit> {
+        let c = Complex64::new(x, y);
+        Some(follow_orbit(
+            c,
+            self.max_iterations,
+            |z| self.bailout.bounded(z),
+            |z| burning_ship(z) + c,
+        ))
+    }
+}
@@ -0,0 +1,715 @@
+use std::str::FromStr;
+
+use crate::fractal::{escape, follow_orbit, powi, trap_orbit, Bailout, EscapeTime, Fractal};
+use crate::fractal::{Orbit, OrbitTrap, SmoothIterations, TrapResult, DEFAULT_MAX_ITERATIONS};
+use num_complex::Complex64;
+
+/// The deepest stack a formula can need to be evaluated.
+const MAX_STACK: usize = 32;
+
+/// The deepest a formula can nest its parentheses, functions and operators,
+/// the parser and the compiler are recursive and must not overflow the stack.
+const MAX_NESTING: usize = 64;
+
+/// The integer powers up to this one are computed using repeated multiplications,
+/// more precise and much faster than the general complex power.
+const MAX_INTEGER_POWER: u32 = 64;
+
+/// Which of `z` and `c` the pixel coordinates are given to.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum FormulaMode {
+    /// `c` is the pixel and the orbit starts from it, like `Mandelbrot` does.
+    Mandelbrot,
+    /// `c` is the given constant and the orbit starts from the pixel, like `Julia` does.
+    Julia(Complex64),
+}
+
+/// A fractal that iterates a formula written by the user, like `z^3 + c*sin(z)`.
+///
+/// The formula can use `z`, `c`, real numbers, imaginary numbers like `2i` or `i`,
+/// the `+ - * / ^` operators, parentheses and the `sin`, `cos`, `tan`, `sinh`, `cosh`,
+/// `tanh`, `exp`, `ln`, `sqrt`, `conj`, `abs` and `norm` functions, `abs` takes
+/// the absolute value of both parts, `abs(z)^2 + c` is the Burning Ship fractal.
+///
+/// The formula is compiled into a small stack based program once.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Formula {
+    source: String,
+    program: Vec<Instruction>,
+    degree: f64,
+    mode: FormulaMode,
+    max_iterations: u32,
+    periodicity_checking: bool,
+    bailout: Bailout,
+}
+
+impl Formula {
+    /// Parses the formula, the pixel is given to `c`.
+    pub fn mandelbrot(source: &str) -> Result<Formula, &'static str> {
+        let expression = Parser::new(source)?.parse()?;
+
+        let mut program = Vec::new();
+        expression.compile(&mut program);
+        if stack_depth(&program) > MAX_STACK {
+            return Err("formula holds too many intermediate values");
+        }
+
+        Ok(Formula {
+            source: source.trim().to_string(),
+            program,
+            degree: expression.degree().max(2.0),
+            mode: FormulaMode::Mandelbrot,
+            max_iterations: DEFAULT_MAX_ITERATIONS,
+            periodicity_checking: false,
+            bailout: Bailout::default(),
+        })
+    }
+
+    /// Parses the formula, `c` is set to the given constant and the pixel is given to `z`.
+    pub fn julia(source: &str, re: f64, im: f64) -> Result<Formula, &'static str> {
+        Formula::mandelbrot(source).map(|formula| formula.to_julia(re, im))
+    }
+
+    /// Returns the same formula in Julia mode with `c` set to the given constant.
+    pub fn to_julia(&self, re: f64, im: f64) -> Formula {
+        Formula { mode: FormulaMode::Julia(Complex64::new(re, im)), ..self.clone() }
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn mode(&self) -> FormulaMode {
+        self.mode
+    }
+
+    /// The growth rate of the formula, the highest power of `z`
+    /// found in it, used to smooth the escape times.
+    pub fn degree(&self) -> f64 {
+        self.degree
+    }
+
+    /// Computes the next value of the orbit.
+    pub fn evaluate(&self, z: Complex64, c: Complex64) -> Complex64 {
+        let mut stack = [Complex64::new(0.0, 0.0); MAX_STACK];
+        let mut len = 0;
+
+        for instruction in &self.program {
+            match *instruction {
+                Instruction::Z => {
+                    stack[len] = z;
+                    len += 1;
+                }
+                Instruction::C => {
+                    stack[len] = c;
+                    len += 1;
+                }
+                Instruction::Constant(value) => {
+                    stack[len] = value;
+                    len += 1;
+                }
+                Instruction::Neg => stack[len - 1] = -stack[len - 1],
+                Instruction::PowI(power) => stack[len - 1] = powi(stack[len - 1], power),
+                Instruction::Call(function) => stack[len - 1] = function.apply(stack[len - 1]),
+                Instruction::Binary(operator) => {
+                    len -= 1;
+                    stack[len - 1] = operator.apply(stack[len - 1], stack[len]);
+                }
+            }
+        }
+
+        stack[0]
+    }
+
+    /// Returns the starting value of the orbit and the value of `c` for this point.
+    fn start(&self, x: f64, y: f64) -> (Complex64, Complex64) {
+        let pixel = Complex64::new(x, y);
+        match self.mode {
+            FormulaMode::Mandelbrot => (pixel, pixel),
+            FormulaMode::Julia(c) => (pixel, c),
+        }
+    }
+
+    fn escape(&self, x: f64, y: f64) -> (u32, Complex64) {
+        let (z, c) = self.start(x, y);
+        escape(
+            z,
+            self.max_iterations,
+            self.periodicity_checking,
+            |z| self.bailout.bounded(z),
+            |z| self.evaluate(z, c),
+        )
+    }
+}
+
+impl FromStr for Formula {
+    type Err = &'static str;
+
+    /// Parses the formula in Mandelbrot mode.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Formula::mandelbrot(s)
+    }
+}
+
+impl Fractal for Formula {
+    fn iterations(&self, x: f64, y: f64) -> EscapeTime {
+        let (iterations, _) = self.escape(x, y);
+        EscapeTime { iterations, max_iterations: self.max_iterations }
+    }
+
+    fn smooth_iterations(&self, x: f64, y: f64) -> SmoothIterations {
+        let (iterations, z) = self.escape(x, y);
+        SmoothIterations::new(iterations, self.max_iterations, z, self.degree)
+    }
+
+    fn max_iterations(&self) -> u32 {
+        self.max_iterations
+    }
+
+    fn set_max_iterations(&mut self, max_iterations: u32) {
+        self.max_iterations = max_iterations;
+    }
+
+    fn set_periodicity_checking(&mut self, enabled: bool) {
+        self.periodicity_checking = enabled;
+    }
+
+    fn set_bailout(&mut self, bailout: Bailout) {
+        self.bailout = bailout;
+    }
+
+    fn orbit_trap(&self, x: f64, y: f64, trap: &OrbitTrap) -> Option<TrapResult> {
+        let (z, c) = self.start(x, y);
+        let bounded = |z| self.bailout.bounded(z);
+        Some(trap_orbit(z, self.max_iterations, trap, bounded, |z| self.evaluate(z, c)))
+    }
+
+    fn orbit(&self, x: f64, y: f64) -> Option<Orbit> {
+        let (z, c) = self.start(x, y);
+        let bounded = |z| self.bailout.bounded(z);
+        Some(follow_orbit(z, self.max_iterations, bounded, |z| self.evaluate(z, c)))
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Function {
+    Sin,
+    Cos,
+    Tan,
+    Sinh,
+    Cosh,
+    Tanh,
+    Exp,
+    Ln,
+    Sqrt,
+    Conj,
+    Abs,
+    Norm,
+}
+
+impl Function {
+    fn from_name(name: &str) -> Option<Function> {
+        match name {
+            "sin" => Some(Function::Sin),
+            "cos" => Some(Function::Cos),
+            "tan" => Some(Function::Tan),
+            "sinh" => Some(Function::Sinh),
+            "cosh" => Some(Function::Cosh),
+            "tanh" => Some(Function::Tanh),
+            "exp" => Some(Function::Exp),
+            "ln" | "log" => Some(Function::Ln),
+            "sqrt" => Some(Function::Sqrt),
+            "conj" => Some(Function::Conj),
+            "abs" => Some(Function::Abs),
+            "norm" => Some(Function::Norm),
+            _ => None,
+        }
+    }
+
+    fn apply(self, z: Complex64) -> Complex64 {
+        match self {
+            Function::Sin => z.sin(),
+            Function::Cos => z.cos(),
+            Function::Tan => z.tan(),
+            Function::Sinh => z.sinh(),
+            Function::Cosh => z.cosh(),
+            Function::Tanh => z.tanh(),
+            Function::Exp => z.exp(),
+            Function::Ln => z.ln(),
+            Function::Sqrt => z.sqrt(),
+            Function::Conj => z.conj(),
+            Function::Abs => Complex64::new(z.re.abs(), z.im.abs()),
+            Function::Norm => Complex64::new(z.norm(), 0.0),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Operator {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Pow,
+}
+
+impl Operator {
+    fn apply(self, a: Complex64, b: Complex64) -> Complex64 {
+        match self {
+            Operator::Add => a + b,
+            Operator::Sub => a - b,
+            Operator::Mul => a * b,
+            Operator::Div => a / b,
+            Operator::Pow => a.powc(b),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Instruction {
+    Z,
+    C,
+    Constant(Complex64),
+    Neg,
+    PowI(u32),
+    Call(Function),
+    Binary(Operator),
+}
+
+/// Returns the maximum number of values the program pushes on the stack at once.
+fn stack_depth(program: &[Instruction]) -> usize {
+    let mut len = 0usize;
+    let mut depth = 0;
+    for instruction in program {
+        match instruction {
+            Instruction::Z | Instruction::C | Instruction::Constant(_) => len += 1,
+            Instruction::Binary(_) => len -= 1,
+            _ => (),
+        }
+        depth = depth.max(len);
+    }
+    depth
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expression {
+    Z,
+    C,
+    Constant(Complex64),
+    Neg(Box<Expression>),
+    Call(Function, Box<Expression>),
+    Binary(Operator, Box<Expression>, Box<Expression>),
+}
+
+impl Expression {
+    /// Returns the value of the expression if it does not depend on `z` nor `c`.
+    fn constant(&self) -> Option<Complex64> {
+        match self {
+            Expression::Z | Expression::C => None,
+            Expression::Constant(value) => Some(*value),
+            Expression::Neg(a) => a.constant().map(|a| -a),
+            Expression::Call(function, a) => a.constant().map(|a| function.apply(a)),
+            Expression::Binary(operator, a, b) => {
+                Some(operator.apply(a.constant()?, b.constant()?))
+            }
+        }
+    }
+
+    /// Returns the exponent if it is a small positive integer.
+    fn integer_power(&self) -> Option<u32> {
+        let power = self.constant()?;
+        let integer = power.re.round();
+        if power.im == 0.0 && power.re == integer && integer >= 0.0 {
+            Some(integer as u32).filter(|&p| p <= MAX_INTEGER_POWER)
+        } else {
+            None
+        }
+    }
+
+    /// Appends the instructions that push the value of the expression on the stack,
+    /// the parts that do not depend on `z` nor `c` are computed once here.
+    fn compile(&self, program: &mut Vec<Instruction>) {
+        if let Some(value) = self.constant() {
+            program.push(Instruction::Constant(value));
+            return;
+        }
+
+        match self {
+            Expression::Z => program.push(Instruction::Z),
+            Expression::C => program.push(Instruction::C),
+            Expression::Constant(value) => program.push(Instruction::Constant(*value)),
+            Expression::Neg(a) => {
+                a.compile(program);
+                program.push(Instruction::Neg);
+            }
+            Expression::Call(function, a) => {
+                a.compile(program);
+                program.push(Instruction::Call(*function));
+            }
+            Expression::Binary(Operator::Pow, a, b) if b.integer_power().is_some() => {
+                a.compile(program);
+                program.push(Instruction::PowI(b.integer_power().unwrap()));
+            }
+            Expression::Binary(operator, a, b) => {
+                a.compile(program);
+                b.compile(program);
+                program.push(Instruction::Binary(*operator));
+            }
+        }
+    }
+
+    /// Estimates the highest power of `z` in the expression.
+    fn degree(&self) -> f64 {
+        match self {
+            Expression::Z => 1.0,
+            Expression::C | Expression::Constant(_) => 0.0,
+            Expression::Neg(a) | Expression::Call(_, a) => a.degree(),
+            Expression::Binary(Operator::Add, a, b) | Expression::Binary(Operator::Sub, a, b) => {
+                a.degree().max(b.degree())
+            }
+            Expression::Binary(Operator::Mul, a, b) => a.degree() + b.degree(),
+            Expression::Binary(Operator::Div, a, b) => a.degree() - b.degree(),
+            Expression::Binary(Operator::Pow, a, b) => match b.constant() {
+                Some(power) => a.degree() * power.re,
+                None => a.degree(),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Imaginary(f64),
+    Identifier(String),
+    Symbol(char),
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, &'static str> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().peekable();
+
+    while let Some(&ch) = chars.peek() {
+        if ch.is_whitespace() {
+            chars.next();
+        } else if ch.is_ascii_digit() || ch == '.' {
+            let mut number = String::new();
+            while let Some(&ch) = chars.peek().filter(|c| c.is_ascii_digit() || **c == '.') {
+                number.push(ch);
+                chars.next();
+            }
+            let number = number.parse().map_err(|_| "invalid number")?;
+            if chars.peek() == Some(&'i') {
+                chars.next();
+                tokens.push(Token::Imaginary(number));
+            } else {
+                tokens.push(Token::Number(number));
+            }
+        } else if ch.is_ascii_alphabetic() {
+            let mut identifier = String::new();
+            while let Some(&ch) = chars.peek().filter(|c| c.is_ascii_alphanumeric()) {
+                identifier.push(ch);
+                chars.next();
+            }
+            tokens.push(Token::Identifier(identifier));
+        } else if "+-*/^()".contains(ch) {
+            tokens.push(Token::Symbol(ch));
+            chars.next();
+        } else {
+            return Err("invalid character in formula");
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// A recursive descent parser, `^` binds tighter than the unary minus,
+/// which binds tighter than `*` and `/`, which bind tighter than `+` and `-`.
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    /// How deep the expression being parsed is nested, see `MAX_NESTING`.
+    nesting: usize,
+}
+
+impl Parser {
+    fn new(source: &str) -> Result<Parser, &'static str> {
+        Ok(Parser { tokens: tokenize(source)?, position: 0, nesting: 0 })
+    }
+
+    fn parse(mut self) -> Result<Expression, &'static str> {
+        if self.tokens.is_empty() {
+            return Err("formula is empty");
+        }
+        let expression = self.sum()?;
+        match self.peek() {
+            None => Ok(expression),
+            Some(Token::Symbol(')')) => Err("unbalanced parenthesis"),
+            Some(_) => Err("expected an operator"),
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn eat(&mut self, symbol: char) -> bool {
+        if self.peek() == Some(&Token::Symbol(symbol)) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Goes one level deeper in the expression, the caller restores the nesting.
+    fn nest(&mut self) -> Result<(), &'static str> {
+        self.nesting += 1;
+        if self.nesting > MAX_NESTING {
+            Err("formula is too deeply nested")
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Every operator of the chain puts the previous operands one level deeper.
+    fn sum(&mut self) -> Result<Expression, &'static str> {
+        let nesting = self.nesting;
+        let mut expression = self.product()?;
+        loop {
+            let operator = if self.eat('+') {
+                Operator::Add
+            } else if self.eat('-') {
+                Operator::Sub
+            } else {
+                self.nesting = nesting;
+                return Ok(expression);
+            };
+            self.nest()?;
+            let right = self.product()?;
+            expression = Expression::Binary(operator, Box::new(expression), Box::new(right));
+        }
+    }
+
+    fn product(&mut self) -> Result<Expression, &'static str> {
+        let nesting = self.nesting;
+        let mut expression = self.unary()?;
+        loop {
+            let operator = if self.eat('*') {
+                Operator::Mul
+            } else if self.eat('/') {
+                Operator::Div
+            } else {
+                self.nesting = nesting;
+                return Ok(expression);
+            };
+            self.nest()?;
+            let right = self.unary()?;
+            expression = Expression::Binary(operator, Box::new(expression), Box::new(right));
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expression, &'static str> {
+        if self.eat('-') {
+            self.nest()?;
+            let expression = Expression::Neg(Box::new(self.unary()?));
+            self.nesting -= 1;
+            Ok(expression)
+        } else if self.eat('+') {
+            self.nest()?;
+            let expression = self.unary()?;
+            self.nesting -= 1;
+            Ok(expression)
+        } else {
+            self.power()
+        }
+    }
+
+    /// The power is right associative, `z^2^3` is `z^(2^3)`.
+    fn power(&mut self) -> Result<Expression, &'static str> {
+        let base = self.atom()?;
+        if self.eat('^') {
+            self.nest()?;
+            let exponent = self.unary()?;
+            self.nesting -= 1;
+            Ok(Expression::Binary(Operator::Pow, Box::new(base), Box::new(exponent)))
+        } else {
+            Ok(base)
+        }
+    }
+
+    /// Parses the expression between parentheses, the opening one is already eaten.
+    fn parenthesized(&mut self) -> Result<Expression, &'static str> {
+        self.nest()?;
+        let expression = self.sum()?;
+        if !self.eat(')') {
+            return Err("unbalanced parenthesis");
+        }
+        self.nesting -= 1;
+        Ok(expression)
+    }
+
+    fn atom(&mut self) -> Result<Expression, &'static str> {
+        match self.next() {
+            Some(Token::Number(value)) => Ok(Expression::Constant(Complex64::new(value, 0.0))),
+            Some(Token::Imaginary(value)) => Ok(Expression::Constant(Complex64::new(0.0, value))),
+            Some(Token::Identifier(ref name)) if name == "z" => Ok(Expression::Z),
+            Some(Token::Identifier(ref name)) if name == "c" => Ok(Expression::C),
+            Some(Token::Identifier(ref name)) if name == "i" => {
+                Ok(Expression::Constant(Complex64::new(0.0, 1.0)))
+            }
+            Some(Token::Identifier(name)) => {
+                let function = Function::from_name(&name).ok_or("unknown variable or function")?;
+                if !self.eat('(') {
+                    return Err("expected a parenthesis after the function name");
+                }
+                let argument = self.parenthesized()?;
+                Ok(Expression::Call(function, Box::new(argument)))
+            }
+            Some(Token::Symbol('(')) => self.parenthesized(),
+            Some(Token::Symbol(_)) => Err("expected a value"),
+            None => Err("unexpected end of formula"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn constant(re: f64, im: f64) -> Expression {
+        Expression::Constant(Complex64::new(re, im))
+    }
+
+    fn binary(operator: Operator, a: Expression, b: Expression) -> Expression {
+        Expression::Binary(operator, Box::new(a), Box::new(b))
+    }
+
+    fn parse(source: &str) -> Result<Expression, &'static str> {
+        Parser::new(source)?.parse()
+    }
+
+    #[test]
+    fn tokenize_formula() {
+        let tokens = tokenize(" z^2 + 1.5i*sin(c) ").unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Identifier("z".to_string()),
+                Token::Symbol('^'),
+                Token::Number(2.0),
+                Token::Symbol('+'),
+                Token::Imaginary(1.5),
+                Token::Symbol('*'),
+                Token::Identifier("sin".to_string()),
+                Token::Symbol('('),
+                Token::Identifier("c".to_string()),
+                Token::Symbol(')'),
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenize_errors() {
+        assert_eq!(tokenize("z $ c"), Err("invalid character in formula"));
+        assert_eq!(tokenize("1.2.3"), Err("invalid number"));
+    }
+
+    #[test]
+    fn parse_precedence() {
+        // the power binds tighter than the unary minus
+        let pow = binary(Operator::Pow, Expression::Z, constant(2.0, 0.0));
+        assert_eq!(parse("-z^2"), Ok(Expression::Neg(Box::new(pow.clone()))));
+
+        let mul = binary(Operator::Mul, Expression::C, Expression::Z);
+        assert_eq!(parse("z^2 + c*z"), Ok(binary(Operator::Add, pow, mul)));
+    }
+
+    #[test]
+    fn parse_associativity() {
+        let pow = binary(Operator::Pow, constant(2.0, 0.0), constant(3.0, 0.0));
+        assert_eq!(parse("z^2^3"), Ok(binary(Operator::Pow, Expression::Z, pow)));
+
+        let sub = binary(Operator::Sub, Expression::Z, Expression::C);
+        assert_eq!(parse("z - c - 1"), Ok(binary(Operator::Sub, sub, constant(1.0, 0.0))));
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(parse(""), Err("formula is empty"));
+        assert_eq!(parse("z +"), Err("unexpected end of formula"));
+        assert_eq!(parse("(z + c"), Err("unbalanced parenthesis"));
+        assert_eq!(parse("z + c)"), Err("unbalanced parenthesis"));
+        assert_eq!(parse("z c"), Err("expected an operator"));
+        assert_eq!(parse("foo(z)"), Err("unknown variable or function"));
+        assert_eq!(parse("sin z"), Err("expected a parenthesis after the function name"));
+        assert_eq!(parse("z * *"), Err("expected a value"));
+    }
+
+    #[test]
+    fn parse_nesting_limit() {
+        let parentheses = format!("{}z{}", "(".repeat(10_000), ")".repeat(10_000));
+        assert_eq!(parse(&parentheses), Err("formula is too deeply nested"));
+
+        let minuses = format!("{}z", "-".repeat(100_000));
+        assert_eq!(parse(&minuses), Err("formula is too deeply nested"));
+
+        let functions = format!("{}z{}", "sin(".repeat(10_000), ")".repeat(10_000));
+        assert_eq!(parse(&functions), Err("formula is too deeply nested"));
+
+        let terms = format!("z{}", "+z".repeat(100_000));
+        assert_eq!(parse(&terms), Err("formula is too deeply nested"));
+
+        let nested = format!("{}z{}", "(".repeat(20), ")".repeat(20));
+        assert_eq!(parse(&nested), Ok(Expression::Z));
+    }
+
+    #[test]
+    fn constant_folding() {
+        let formula = Formula::mandelbrot("z^(1 + 1) + (1 + 2i) * 2").unwrap();
+        assert_eq!(
+            formula.program,
+            vec![
+                Instruction::Z,
+                Instruction::PowI(2),
+                Instruction::Constant(Complex64::new(2.0, 4.0)),
+                Instruction::Binary(Operator::Add),
+            ]
+        );
+
+        // only the small positive integer powers use repeated multiplications
+        let formula = Formula::mandelbrot("z^0.5").unwrap();
+        assert_eq!(
+            formula.program,
+            vec![
+                Instruction::Z,
+                Instruction::Constant(Complex64::new(0.5, 0.0)),
+                Instruction::Binary(Operator::Pow),
+            ]
+        );
+    }
+
+    #[test]
+    fn evaluate_and_degree() {
+        let formula = Formula::mandelbrot("z^3 + c*sin(z)").unwrap();
+        let (z, c) = (Complex64::new(0.3, -0.2), Complex64::new(-0.5, 0.1));
+        assert_eq!(formula.evaluate(z, c), z * z * z + c * z.sin());
+        assert_eq!(formula.degree(), 3.0);
+    }
+
+    #[test]
+    fn stack_limit() {
+        // every power of the right associative chain waits for its exponent on the stack
+        let source = format!("{}z", "z^".repeat(40));
+        assert_eq!(
+            Formula::mandelbrot(&source).map(|_| ()),
+            Err("formula holds too many intermediate values")
+        );
+    }
+}
@@ -1,27 +1,122 @@
-use crate::fractal::Fractal;
+use crate::fractal::Bailout;
+use crate::fractal::{escape, exterior_distance, trap_orbit, EscapeTime, Fractal};
+use crate::fractal::{follow_orbit, Orbit};
+use crate::fractal::{quadratic_batch, LaneBailout, QuadraticBatch};
+use crate::fractal::{OrbitTrap, SmoothIterations, TrapResult};
+use crate::fractal::{DEFAULT_MAX_ITERATIONS, DISTANCE_ESCAPE_RADIUS};
 use num_complex::Complex64;
 
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub struct Julia {
     c: Complex64,
+    max_iterations: u32,
+    periodicity_checking: bool,
+    bailout: Bailout,
 }
 
 impl Julia {
     pub fn new(re: f64, im: f64) -> Julia {
-        Julia { c: Complex64::new(re, im) }
+        Julia {
+            c: Complex64::new(re, im),
+            max_iterations: DEFAULT_MAX_ITERATIONS,
+            periodicity_checking: false,
+            bailout: Bailout::default(),
+        }
+    }
+
+    fn bounded(&self, z: Complex64) -> bool {
+        self.bailout.bounded_with_legacy(z, legacy_bounded)
+    }
+
+    fn escape(&self, x: f64, y: f64) -> (u32, Complex64) {
+        let z = Complex64::new(x, y);
+        escape(
+            z,
+            self.max_iterations,
+            self.periodicity_checking,
+            |z| self.bounded(z),
+            |z| z * z + self.c,
+        )
+    }
+
+    /// The batch evaluating the orbits with SIMD, `None` if the bailout can not be vectorized.
+    fn batch(&self) -> Option<QuadraticBatch> {
+        let bailout = LaneBailout::from_bailout(&self.bailout, LaneBailout::LegacyDouble)?;
+        Some(QuadraticBatch {
+            max_iterations: self.max_iterations,
+            periodicity_checking: self.periodicity_checking,
+            bailout,
+            julia: Some([self.c.re, self.c.im]),
+        })
     }
 }
 
+/// The test used before bailouts were configurable.
+fn legacy_bounded(z: Complex64) -> bool {
+    (z + z).re <= 4.0
+}
+
 impl Fractal for Julia {
-    fn iterations(&self, x: f64, y: f64) -> u8 {
-        let mut iterations = 0;
+    fn iterations(&self, x: f64, y: f64) -> EscapeTime {
+        let (iterations, _) = self.escape(x, y);
+        EscapeTime { iterations, max_iterations: self.max_iterations }
+    }
+
+    fn smooth_iterations(&self, x: f64, y: f64) -> SmoothIterations {
+        let (iterations, z) = self.escape(x, y);
+        SmoothIterations::new(iterations, self.max_iterations, z, 2.0)
+    }
+
+    fn max_iterations(&self) -> u32 {
+        self.max_iterations
+    }
+
+    fn set_max_iterations(&mut self, max_iterations: u32) {
+        self.max_iterations = max_iterations;
+    }
+
+    fn iterations_batch(&self, xs: &[f64], ys: &[f64], escapes: &mut [EscapeTime]) {
+        match self.batch() {
+            Some(batch) => quadratic_batch(&batch, xs, ys, escapes, |_, _| false),
+            None => {
+                for ((x, y), escape) in xs.iter().zip(ys).zip(escapes) {
+                    *escape = self.iterations(*x, *y);
+                }
+            }
+        }
+    }
+
+    fn set_periodicity_checking(&mut self, enabled: bool) {
+        self.periodicity_checking = enabled;
+    }
+
+    fn set_bailout(&mut self, bailout: Bailout) {
+        self.bailout = bailout;
+    }
+
+    fn distance_estimate(&self, x: f64, y: f64) -> Option<f64> {
+        // the derivative is taken with respect to the starting point
         let mut z = Complex64::new(x, y);
+        let mut dz = Complex64::new(1.0, 0.0);
 
-        while (z + z).re <= 4.0 && iterations < u8::max_value() {
+        for _ in 0..self.max_iterations {
+            if z.norm_sqr() > DISTANCE_ESCAPE_RADIUS {
+                return Some(exterior_distance(z, dz));
+            }
+            dz = (z * dz).scale(2.0);
             z = z * z + self.c;
-            iterations += 1;
         }
 
-        iterations
+        Some(0.0)
+    }
+
+    fn orbit_trap(&self, x: f64, y: f64, trap: &OrbitTrap) -> Option<TrapResult> {
+        let z = Complex64::new(x, y);
+        Some(trap_orbit(z, self.max_iterations, trap, |z| self.bounded(z), |z| z * z + self.c))
+    }
+
+    fn orbit(&self, x: f64, y: f64) -> Option<Orbit> {
+        let z = Complex64::new(x, y);
+        Some(follow_orbit(z, self.max_iterations, |z| self.bounded(z), |z| z * z + self.c))
     }
 }
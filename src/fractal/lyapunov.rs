@@ -0,0 +1,113 @@
+use std::str::FromStr;
+
+use crate::fractal::{EscapeTime, Fractal, SmoothIterations};
+
+/// The number of iterations done before measuring the exponent,
+/// the orbit has to settle on its attractor first.
+const WARMUP_ITERATIONS: u32 = 64;
+
+/// The iteration count used to measure the exponent when none is specified.
+pub const LYAPUNOV_MAX_ITERATIONS: u32 = 200;
+
+/// The Lyapunov fractal, the world coordinates `(a, b)` are the growth rates
+/// of the logistic map `x' = r·x·(1 - x)`, `r` alternates between them following a sequence.
+///
+/// The Lyapunov exponent of the map tells whether it is stable (negative) or chaotic (positive).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lyapunov {
+    /// The sequence of the growth rates, `true` stands for `b`.
+    sequence: Vec<bool>,
+    max_iterations: u32,
+}
+
+impl Lyapunov {
+    /// Creates the fractal of a sequence like `"AABAB"`.
+    pub fn new(sequence: &str) -> Result<Lyapunov, &'static str> {
+        let sequence = sequence
+            .trim()
+            .chars()
+            .map(|c| match c.to_ascii_uppercase() {
+                'A' => Ok(false),
+                'B' => Ok(true),
+                _ => Err("sequence must only contain A and B"),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if sequence.is_empty() {
+            return Err("sequence can not be empty");
+        }
+
+        Ok(Lyapunov { sequence, max_iterations: LYAPUNOV_MAX_ITERATIONS })
+    }
+
+    /// Returns the sequence using `A` and `B`.
+    pub fn sequence(&self) -> String {
+        self.sequence.iter().map(|&b| if b { 'B' } else { 'A' }).collect()
+    }
+
+    /// Returns the Lyapunov exponent of the map at the given growth rates,
+    /// negative when the map is stable, positive when it is chaotic.
+    ///
+    /// The map diverges when a rate is outside `[0, 4]`, the exponent is then not finite.
+    pub fn exponent(&self, a: f64, b: f64) -> f64 {
+        let mut x = 0.5;
+        let mut sum = 0.0;
+
+        let rates = self.sequence.iter().map(|&b_rate| if b_rate { b } else { a }).cycle();
+        for (i, r) in rates.take((WARMUP_ITERATIONS + self.max_iterations) as usize).enumerate() {
+            if i >= WARMUP_ITERATIONS as usize {
+                // the logarithm of the derivative of the map
+                sum += (r * (1.0 - 2.0 * x)).abs().ln();
+            }
+            x = r * x * (1.0 - x);
+        }
+
+        sum / self.max_iterations as f64
+    }
+
+    /// Maps the exponent of the stable points in `[0, 1[`,
+    /// the most stable ones are the closest to zero.
+    fn stability(&self, x: f64, y: f64) -> Option<f64> {
+        let exponent = self.exponent(x, y);
+        if exponent < 0.0 {
+            Some(exponent.exp())
+        } else {
+            None
+        }
+    }
+}
+
+impl FromStr for Lyapunov {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Lyapunov::new(s)
+    }
+}
+
+/// The stable points are considered escaping, the chaotic ones are considered
+/// inside the fractal, the exponent itself is given by `Lyapunov::exponent`.
+impl Fractal for Lyapunov {
+    fn iterations(&self, x: f64, y: f64) -> EscapeTime {
+        let iterations = match self.stability(x, y) {
+            Some(stability) => (stability * self.max_iterations as f64) as u32,
+            None => self.max_iterations,
+        };
+        EscapeTime { iterations, max_iterations: self.max_iterations }
+    }
+
+    fn smooth_iterations(&self, x: f64, y: f64) -> SmoothIterations {
+        match self.stability(x, y) {
+            Some(stability) => SmoothIterations { value: stability, escaped: true },
+            None => SmoothIterations { value: 1.0, escaped: false },
+        }
+    }
+
+    fn max_iterations(&self) -> u32 {
+        self.max_iterations
+    }
+
+    fn set_max_iterations(&mut self, max_iterations: u32) {
+        self.max_iterations = max_iterations;
+    }
+}
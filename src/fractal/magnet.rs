@@ -0,0 +1,120 @@
+use num_complex::Complex64;
+use rand_derive::Rand;
+
+use crate::fractal::{Bailout, ConvergenceBailout};
+use crate::fractal::{EscapeTime, Fractal, SmoothIterations, DEFAULT_MAX_ITERATIONS};
+
+/// The rational maps of the Magnet fractals, both come from models of magnetism.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Rand)]
+pub enum MagnetKind {
+    /// `z' = ((z² + c - 1) / (2z + c - 2))²`
+    TypeI,
+    /// `z' = ((z³ + 3(c - 1)z + (c - 1)(c - 2)) / (3z² + 3(c - 2)z + (c - 1)(c - 2) + 1))²`
+    TypeII,
+}
+
+/// The Magnet fractals, `c` is the point and the orbit starts at zero.
+///
+/// The orbits can escape to infinity like the Mandelbrot ones
+/// but they can also converge to a finite attractor, `z = 1` is one of them.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Magnet {
+    kind: MagnetKind,
+    max_iterations: u32,
+    bailout: Bailout,
+    convergence: ConvergenceBailout,
+}
+
+impl Magnet {
+    pub fn new(kind: MagnetKind) -> Magnet {
+        Magnet {
+            kind,
+            max_iterations: DEFAULT_MAX_ITERATIONS,
+            // the orbits can go past the usual radius of 2 and still converge
+            bailout: Bailout::radius(100.0),
+            convergence: ConvergenceBailout::default(),
+        }
+    }
+
+    pub fn kind(&self) -> MagnetKind {
+        self.kind
+    }
+
+    fn step(&self, z: Complex64, c: Complex64) -> Complex64 {
+        let one = Complex64::new(1.0, 0.0);
+        let two = Complex64::new(2.0, 0.0);
+
+        let w = match self.kind {
+            MagnetKind::TypeI => (z * z + c - one) / (z.scale(2.0) + c - two),
+            MagnetKind::TypeII => {
+                let c1 = c - one;
+                let c2 = c - two;
+                (z * z * z + (c1 * z).scale(3.0) + c1 * c2)
+                    / ((z * z).scale(3.0) + (c2 * z).scale(3.0) + c1 * c2 + one)
+            }
+        };
+        w * w
+    }
+
+    /// Iterates the point until its orbit escapes or converges,
+    /// returns the iteration count, the last value and whether it converged.
+    fn escape(&self, x: f64, y: f64) -> (u32, Complex64, bool) {
+        let c = Complex64::new(x, y);
+        let mut z = Complex64::new(0.0, 0.0);
+        let mut iterations = 0;
+
+        while iterations < self.max_iterations {
+            if !self.bailout.bounded(z) {
+                return (iterations, z, false);
+            }
+
+            let previous = z;
+            z = self.step(z, c);
+            iterations += 1;
+
+            if self.convergence.converged(previous, z) {
+                return (iterations, z, true);
+            }
+        }
+
+        (iterations, z, false)
+    }
+}
+
+/// The points whose orbit converged are considered escaped as well,
+/// only the orbits that neither escape nor converge are inside the fractal.
+impl Fractal for Magnet {
+    fn iterations(&self, x: f64, y: f64) -> EscapeTime {
+        let (iterations, _, _) = self.escape(x, y);
+        EscapeTime { iterations, max_iterations: self.max_iterations }
+    }
+
+    fn smooth_iterations(&self, x: f64, y: f64) -> SmoothIterations {
+        match self.escape(x, y) {
+            (iterations, _, true) => SmoothIterations {
+                value: iterations as f64 / self.max_iterations as f64,
+                escaped: true,
+            },
+            // the map behaves like `z²` far from the origin
+            (iterations, z, false) => {
+                SmoothIterations::new(iterations, self.max_iterations, z, 2.0)
+            }
+        }
+    }
+
+    fn max_iterations(&self) -> u32 {
+        self.max_iterations
+    }
+
+    fn set_max_iterations(&mut self, max_iterations: u32) {
+        self.max_iterations = max_iterations;
+    }
+
+    fn set_bailout(&mut self, bailout: Bailout) {
+        self.bailout = bailout;
+    }
+
+    fn set_convergence_bailout(&mut self, convergence: ConvergenceBailout) {
+        self.convergence = convergence;
+    }
+}
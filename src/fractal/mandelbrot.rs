@@ -1,26 +1,199 @@
-use crate::fractal::Fractal;
+use crate::fractal::Bailout;
+use crate::fractal::{escape, exterior_distance, trap_orbit, EscapeTime, Fractal};
+use crate::fractal::{follow_orbit, Orbit};
+use crate::fractal::{quadratic_batch, LaneBailout, QuadraticBatch};
+use crate::fractal::{OrbitTrap, SmoothIterations, TrapResult};
+use crate::fractal::{DEFAULT_MAX_ITERATIONS, DISTANCE_ESCAPE_RADIUS};
 use num_complex::Complex64;
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
-pub struct Mandelbrot;
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Mandelbrot {
+    max_iterations: u32,
+    periodicity_checking: bool,
+    bailout: Bailout,
+}
 
 impl Mandelbrot {
     pub fn new() -> Mandelbrot {
-        Mandelbrot
+        Mandelbrot {
+            max_iterations: DEFAULT_MAX_ITERATIONS,
+            periodicity_checking: false,
+            bailout: Bailout::default(),
+        }
+    }
+
+    fn bounded(&self, z: Complex64) -> bool {
+        self.bailout.bounded_with_legacy(z, legacy_bounded)
+    }
+
+    fn escape(&self, x: f64, y: f64) -> (u32, Complex64) {
+        let c = Complex64::new(x, y);
+        if self.never_escapes(c) {
+            return (self.max_iterations, c);
+        }
+        escape(
+            c,
+            self.max_iterations,
+            self.periodicity_checking,
+            |z| self.bounded(z),
+            |z| z * z + c,
+        )
+    }
+
+    /// The batch evaluating the orbits with SIMD, `None` if the bailout can not be vectorized.
+    fn batch(&self) -> Option<QuadraticBatch> {
+        let bailout = LaneBailout::from_bailout(&self.bailout, LaneBailout::LegacySquare)?;
+        Some(QuadraticBatch {
+            max_iterations: self.max_iterations,
+            periodicity_checking: self.periodicity_checking,
+            bailout,
+            julia: None,
+        })
+    }
+
+    /// Whether the point can skip the iterations, the points of the cardioid
+    /// and the bulb never go farther than 2 from the origin.
+    fn never_escapes(&self, c: Complex64) -> bool {
+        self.bailout.bounds_disk(2.0) && (in_main_cardioid(c) || in_period2_bulb(c))
+    }
+}
+
+/// The test used before bailouts were configurable.
+pub(crate) fn legacy_bounded(z: Complex64) -> bool {
+    (z * z).re <= 4.0
+}
+
+/// Whether `c` is inside the main cardioid, these points never escape.
+pub(crate) fn in_main_cardioid(c: Complex64) -> bool {
+    let x = c.re - 0.25;
+    let q = x * x + c.im * c.im;
+    q * (q + x) <= 0.25 * c.im * c.im
+}
+
+/// Whether `c` is inside the period-2 bulb, the disk
+/// on the left of the main cardioid, these points never escape.
+pub(crate) fn in_period2_bulb(c: Complex64) -> bool {
+    let x = c.re + 1.0;
+    x * x + c.im * c.im <= 0.0625
+}
+
+/// Estimates the distance from `c` to the boundary of the Mandelbrot set
+/// using the attracting cycle its orbit fell into, `z` must be close to this cycle.
+///
+/// https://en.wikipedia.org/wiki/Plotting_algorithms_for_the_Mandelbrot_set#Interior_distance_estimation
+fn interior_distance(c: Complex64, z: Complex64, period: u32) -> Option<f64> {
+    let zero = Complex64::new(0.0, 0.0);
+    let one = Complex64::new(1.0, 0.0);
+
+    // a few Newton steps on `f(z0) = z_period(z0) - z0` find the exact point of the cycle
+    let mut z0 = z;
+    for _ in 0..8 {
+        let (mut z, mut dz) = (z0, one);
+        for _ in 0..period {
+            dz = (z * dz).scale(2.0);
+            z = z * z + c;
+        }
+        let step = (z - z0) / (dz - one);
+        if !step.re.is_finite() || !step.im.is_finite() {
+            return None;
+        }
+        z0 -= step;
+    }
+
+    // the derivatives of a whole period, with respect to `z` and `c`
+    let (mut z, mut dz, mut dc, mut dzdz, mut dzdc) = (z0, one, zero, zero, zero);
+    for _ in 0..period {
+        dzdz = (z * dzdz + dz * dz).scale(2.0);
+        dzdc = (z * dzdc + dz * dc).scale(2.0);
+        dc = (z * dc).scale(2.0) + one;
+        dz = (z * dz).scale(2.0);
+        z = z * z + c;
+    }
+
+    // only attracting cycles give a distance
+    if dz.norm_sqr() >= 1.0 {
+        return None;
+    }
+
+    let distance = (1.0 - dz.norm_sqr()) / (dzdc + dzdz * dc / (one - dz)).norm();
+    Some(distance).filter(|d| d.is_finite())
+}
+
+impl Default for Mandelbrot {
+    fn default() -> Mandelbrot {
+        Mandelbrot::new()
     }
 }
 
 impl Fractal for Mandelbrot {
-    fn iterations(&self, x: f64, y: f64) -> u8 {
-        let mut iterations = 0;
+    fn iterations(&self, x: f64, y: f64) -> EscapeTime {
+        let (iterations, _) = self.escape(x, y);
+        EscapeTime { iterations, max_iterations: self.max_iterations }
+    }
+
+    fn smooth_iterations(&self, x: f64, y: f64) -> SmoothIterations {
+        let (iterations, z) = self.escape(x, y);
+        SmoothIterations::new(iterations, self.max_iterations, z, 2.0)
+    }
+
+    fn max_iterations(&self) -> u32 {
+        self.max_iterations
+    }
+
+    fn set_max_iterations(&mut self, max_iterations: u32) {
+        self.max_iterations = max_iterations;
+    }
+
+    fn iterations_batch(&self, xs: &[f64], ys: &[f64], escapes: &mut [EscapeTime]) {
+        match self.batch() {
+            Some(batch) => quadratic_batch(&batch, xs, ys, escapes, |x, y| {
+                self.never_escapes(Complex64::new(x, y))
+            }),
+            None => {
+                for ((x, y), escape) in xs.iter().zip(ys).zip(escapes) {
+                    *escape = self.iterations(*x, *y);
+                }
+            }
+        }
+    }
+
+    fn set_periodicity_checking(&mut self, enabled: bool) {
+        self.periodicity_checking = enabled;
+    }
+
+    fn set_bailout(&mut self, bailout: Bailout) {
+        self.bailout = bailout;
+    }
+
+    fn distance_estimate(&self, x: f64, y: f64) -> Option<f64> {
+        // the derivative is taken with respect to `c`
         let c = Complex64::new(x, y);
-        let mut z = c;
+        let one = Complex64::new(1.0, 0.0);
+        let (mut z, mut dz) = (c, one);
 
-        while (z * z).re <= 4.0 && iterations < u8::max_value() {
+        for _ in 0..self.max_iterations {
+            if z.norm_sqr() > DISTANCE_ESCAPE_RADIUS {
+                return Some(exterior_distance(z, dz));
+            }
+            dz = (z * dz).scale(2.0) + one;
             z = z * z + c;
-            iterations += 1;
         }
 
-        iterations
+        Some(0.0)
+    }
+
+    fn orbit_trap(&self, x: f64, y: f64, trap: &OrbitTrap) -> Option<TrapResult> {
+        let c = Complex64::new(x, y);
+        Some(trap_orbit(c, self.max_iterations, trap, |z| self.bounded(z), |z| z * z + c))
+    }
+
+    fn orbit(&self, x: f64, y: f64) -> Option<Orbit> {
+        let c = Complex64::new(x, y);
+        let bounded = |z: Complex64| self.bounded(z);
+        let mut orbit = follow_orbit(c, self.max_iterations, bounded, |z| z * z + c);
+        if let Some(period) = orbit.period {
+            orbit.interior_distance = interior_distance(c, orbit.last, period);
+        }
+        Some(orbit)
     }
 }
@@ -1,17 +1,312 @@
+mod bailout;
+mod batch;
+mod burning_ship;
+mod burning_ship_julia;
+mod formula;
 mod julia;
+mod lyapunov;
+mod magnet;
 mod mandelbrot;
+mod multibrot;
+mod multibrot_julia;
+mod newton;
+mod nova;
+mod nova_julia;
+mod orbit;
+mod orbit_trap;
+mod perturbation;
+mod phoenix;
+mod tricorn;
+mod tricorn_julia;
 
-use std::ops::Deref;
+use std::f64::consts::LN_2;
+use std::ops::{Deref, DerefMut};
 
+use num_complex::Complex64;
+
+pub(crate) use self::batch::{quadratic_batch, LaneBailout, QuadraticBatch};
+pub(crate) use self::mandelbrot::{in_main_cardioid, in_period2_bulb};
+pub(crate) use self::nova::{nova_convergence, nova_smooth_iterations};
+pub(crate) use self::orbit::follow_orbit;
+pub(crate) use self::orbit_trap::trap_orbit;
+
+pub use self::bailout::{Bailout, ConvergenceBailout, Norm};
+pub use self::burning_ship::BurningShip;
+pub use self::burning_ship_julia::BurningShipJulia;
+pub use self::formula::{Formula, FormulaMode};
 pub use self::julia::Julia;
+pub use self::lyapunov::{Lyapunov, LYAPUNOV_MAX_ITERATIONS};
+pub use self::magnet::{Magnet, MagnetKind};
 pub use self::mandelbrot::Mandelbrot;
+pub use self::multibrot::Multibrot;
+pub use self::multibrot_julia::MultibrotJulia;
+pub use self::newton::{Convergence, Newton, NEWTON_MAX_ITERATIONS};
+pub use self::nova::Nova;
+pub use self::nova_julia::NovaJulia;
+pub use self::orbit::{InteriorColoring, Orbit};
+pub use self::orbit_trap::{OrbitTrap, TrapColoring, TrapResult};
+pub use self::perturbation::{PerturbedMandelbrot, ReferenceOrbit};
+pub use self::phoenix::Phoenix;
+pub use self::tricorn::Tricorn;
+pub use self::tricorn_julia::TricornJulia;
+
+/// The iteration limit used by fractals when none is specified.
+pub const DEFAULT_MAX_ITERATIONS: u32 = 255;
+
+/// The number of iterations a point took to escape
+/// along with the limit that was used to compute it.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct EscapeTime {
+    pub iterations: u32,
+    pub max_iterations: u32,
+}
+
+impl EscapeTime {
+    /// Whether the point escaped before the iteration limit.
+    pub fn escaped(&self) -> bool {
+        self.iterations < self.max_iterations
+    }
+
+    /// Returns the iterations normalized in `[0, 1]`.
+    pub fn ratio(&self) -> f64 {
+        if self.max_iterations == 0 {
+            return 1.0;
+        }
+        self.iterations as f64 / self.max_iterations as f64
+    }
+
+    /// Returns the iterations scaled to `[0, 255]`, useful for grayscale images.
+    pub fn to_u8(&self) -> u8 {
+        if self.max_iterations == 0 {
+            return u8::MAX;
+        }
+        (self.iterations as u64 * u8::MAX as u64 / self.max_iterations as u64) as u8
+    }
+}
+
+/// The continuous escape value of a point, free of the color banding
+/// produced by integer iteration counts.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct SmoothIterations {
+    /// The fractional iteration count normalized in `[0, 1]`.
+    pub value: f64,
+    /// Whether the point escaped before the iteration limit.
+    pub escaped: bool,
+}
+
+impl SmoothIterations {
+    /// Smooths an escape time using the normalized iteration count (log-log) formula,
+    /// `z` is the first orbit value that went past the bailout
+    /// and `degree` the power of the iterated polynomial.
+    pub(crate) fn new(
+        iterations: u32,
+        max_iterations: u32,
+        z: Complex64,
+        degree: f64,
+    ) -> SmoothIterations {
+        if iterations >= max_iterations {
+            return SmoothIterations { value: 1.0, escaped: false };
+        }
+
+        let log_zn = z.norm_sqr().ln() / 2.0;
+        let nu = (log_zn / LN_2).ln() / degree.ln();
+
+        // orbits that overflowed before reaching the bailout can not be smoothed
+        let smooth = if nu.is_finite() { iterations as f64 + 1.0 - nu } else { iterations as f64 };
+
+        let value = (smooth / max_iterations as f64).clamp(0.0, 1.0);
+        SmoothIterations { value, escaped: true }
+    }
+}
+
+/// The squared distance under which the orbit is considered to have come back
+/// to a previous value, small enough to never stop an orbit that would escape.
+pub(crate) const PERIODICITY_TOLERANCE: f64 = 1e-24;
+
+/// The number of iterations between two comparisons of the orbit to the saved value,
+/// comparing at every iteration made the frames where most points escape slower.
+pub(crate) const PERIODICITY_INTERVAL: u32 = 8;
+
+/// Iterates `z` using the `step` function while it stays `bounded`,
+/// returns the number of iterations done and the last value of the orbit.
+///
+/// When `periodicity_checking` is enabled the orbits that fall into a cycle are stopped
+/// early and reported as if they reached the limit, the cycles are found using
+/// the Brent's algorithm: the orbit is compared to a saved value that is moved
+/// forward each time the number of iterations done since the last save doubles.
+/// The comparison is only done every `PERIODICITY_INTERVAL` iterations, the cycles are
+/// found a bit later but the orbits that escape are not slowed down by the check.
+pub(crate) fn escape<B, S>(
+    mut z: Complex64,
+    max_iterations: u32,
+    periodicity_checking: bool,
+    bounded: B,
+    step: S,
+) -> (u32, Complex64)
+where
+    B: Fn(Complex64) -> bool,
+    S: Fn(Complex64) -> Complex64,
+{
+    let mut iterations = 0;
+    let mut saved = z;
+    let mut power = 1;
+    let mut length = 0;
+
+    while bounded(z) && iterations < max_iterations {
+        z = step(z);
+        iterations += 1;
+
+        if periodicity_checking {
+            if iterations % PERIODICITY_INTERVAL == 0
+                && (z - saved).norm_sqr() < PERIODICITY_TOLERANCE
+            {
+                return (max_iterations, z);
+            }
+
+            length += 1;
+            if length == power {
+                saved = z;
+                power *= 2;
+                length = 0;
+            }
+        }
+    }
+
+    (iterations, z)
+}
+
+/// The squared escape radius used when estimating distances,
+/// the estimation is only accurate for large radii.
+pub(crate) const DISTANCE_ESCAPE_RADIUS: f64 = 1e10;
+
+/// Returns the exterior distance estimate of an escaped orbit
+/// from its last value and the derivative of this value.
+///
+/// http://www.iquilezles.org/www/articles/distancefractals/distancefractals.htm
+pub(crate) fn exterior_distance(z: Complex64, dz: Complex64) -> f64 {
+    let r = z.norm();
+    0.5 * r * r.ln() / dz.norm()
+}
+
+/// Raises `z` to a positive integer `power` using repeated multiplications.
+pub(crate) fn powi(z: Complex64, power: u32) -> Complex64 {
+    let mut result = Complex64::new(1.0, 0.0);
+    for _ in 0..power {
+        result *= z;
+    }
+    result
+}
 
 pub trait Fractal {
-    fn iterations(&self, x: f64, y: f64) -> u8;
+    fn iterations(&self, x: f64, y: f64) -> EscapeTime;
+
+    /// Returns the continuous escape value of the point,
+    /// useful to sample a gradient without visible bands.
+    fn smooth_iterations(&self, x: f64, y: f64) -> SmoothIterations;
+
+    /// The number of iterations after which a point is considered inside the fractal.
+    fn max_iterations(&self) -> u32;
+
+    fn set_max_iterations(&mut self, max_iterations: u32);
+
+    /// Computes the escape times of a batch of points given by their world coordinates,
+    /// `xs`, `ys` and `escapes` must have the same length.
+    ///
+    /// The results are the same as calling `iterations` on every point. Only `Mandelbrot`
+    /// and `Julia` override it to evaluate several points at once using SIMD,
+    /// the other fractals call `iterations` in a loop.
+    fn iterations_batch(&self, xs: &[f64], ys: &[f64], escapes: &mut [EscapeTime]) {
+        assert!(
+            xs.len() == escapes.len() && ys.len() == escapes.len(),
+            "batch coordinates and escapes must have the same length"
+        );
+        for ((x, y), escape) in xs.iter().zip(ys).zip(escapes) {
+            *escape = self.iterations(*x, *y);
+        }
+    }
+
+    /// Stops the iteration of the points whose orbits fall into a cycle, these points
+    /// never escape, it speeds up the rendering of the areas inside the fractal.
+    ///
+    /// Fractals that do not support it ignore this setting.
+    fn set_periodicity_checking(&mut self, _enabled: bool) {}
+
+    /// Changes the test that decides whether an orbit escaped.
+    ///
+    /// Fractals that do not iterate until escape ignore this setting.
+    fn set_bailout(&mut self, _bailout: Bailout) {}
+
+    /// Changes the test that decides whether an orbit converged to a finite attractor.
+    ///
+    /// Fractals that only escape ignore this setting.
+    fn set_convergence_bailout(&mut self, _convergence: ConvergenceBailout) {}
+
+    /// Returns an estimation of the distance from the point to the fractal boundary
+    /// in world coordinates, zero for the points inside the fractal.
+    ///
+    /// Returns `None` if the fractal does not support distance estimation.
+    fn distance_estimate(&self, _x: f64, _y: f64) -> Option<f64> {
+        None
+    }
+
+    /// Follows the orbit of the point and returns how close it came to the `trap`.
+    ///
+    /// Returns `None` if the fractal does not support orbit traps.
+    fn orbit_trap(&self, _x: f64, _y: f64, _trap: &OrbitTrap) -> Option<TrapResult> {
+        None
+    }
+
+    /// Follows the orbit of the point and returns what it looks like,
+    /// useful to color the points inside the fractal.
+    ///
+    /// Returns `None` if the fractal does not expose its orbits.
+    fn orbit(&self, _x: f64, _y: f64) -> Option<Orbit> {
+        None
+    }
 }
 
 impl<T: Fractal + ?Sized> Fractal for Box<T> {
-    fn iterations(&self, x: f64, y: f64) -> u8 {
+    fn iterations(&self, x: f64, y: f64) -> EscapeTime {
         self.deref().iterations(x, y)
     }
+
+    fn smooth_iterations(&self, x: f64, y: f64) -> SmoothIterations {
+        self.deref().smooth_iterations(x, y)
+    }
+
+    fn max_iterations(&self) -> u32 {
+        self.deref().max_iterations()
+    }
+
+    fn set_max_iterations(&mut self, max_iterations: u32) {
+        self.deref_mut().set_max_iterations(max_iterations)
+    }
+
+    fn iterations_batch(&self, xs: &[f64], ys: &[f64], escapes: &mut [EscapeTime]) {
+        self.deref().iterations_batch(xs, ys, escapes)
+    }
+
+    fn set_periodicity_checking(&mut self, enabled: bool) {
+        self.deref_mut().set_periodicity_checking(enabled)
+    }
+
+    fn set_bailout(&mut self, bailout: Bailout) {
+        self.deref_mut().set_bailout(bailout)
+    }
+
+    fn set_convergence_bailout(&mut self, convergence: ConvergenceBailout) {
+        self.deref_mut().set_convergence_bailout(convergence)
+    }
+
+    fn distance_estimate(&self, x: f64, y: f64) -> Option<f64> {
+        self.deref().distance_estimate(x, y)
+    }
+
+    fn orbit_trap(&self, x: f64, y: f64, trap: &OrbitTrap) -> Option<TrapResult> {
+        self.deref().orbit_trap(x, y, trap)
+    }
+
+    fn orbit(&self, x: f64, y: f64) -> Option<Orbit> {
+        self.deref().orbit(x, y)
+    }
 }
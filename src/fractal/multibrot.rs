@@ -0,0 +1,78 @@
+use crate::fractal::Bailout;
+use crate::fractal::{escape, powi, EscapeTime, Fractal, SmoothIterations, DEFAULT_MAX_ITERATIONS};
+use crate::fractal::{follow_orbit, Orbit};
+use num_complex::Complex64;
+
+/// The generalization of the Mandelbrot fractal to higher powers, iterates `z^power + c`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Multibrot {
+    power: u32,
+    max_iterations: u32,
+    periodicity_checking: bool,
+    bailout: Bailout,
+}
+
+impl Multibrot {
+    pub fn new(power: u32) -> Multibrot {
+        assert!(power >= 2, "multibrot power must be greater than one");
+        Multibrot {
+            power,
+            max_iterations: DEFAULT_MAX_ITERATIONS,
+            periodicity_checking: false,
+            bailout: Bailout::default(),
+        }
+    }
+
+    pub fn power(&self) -> u32 {
+        self.power
+    }
+
+    fn escape(&self, x: f64, y: f64) -> (u32, Complex64) {
+        let c = Complex64::new(x, y);
+        escape(
+            c,
+            self.max_iterations,
+            self.periodicity_checking,
+            |z| self.bailout.bounded(z),
+            |z| powi(z, self.power) + c,
+        )
+    }
+}
+
+impl Fractal for Multibrot {
+    fn iterations(&self, x: f64, y: f64) -> EscapeTime {
+        let (iterations, _) = self.escape(x, y);
+        EscapeTime { iterations, max_iterations: self.max_iterations }
+    }
+
+    fn smooth_iterations(&self, x: f64, y: f64) -> SmoothIterations {
+        let (iterations, z) = self.escape(x, y);
+        SmoothIterations::new(iterations, self.max_iterations, z, self.power as f64)
+    }
+
+    fn max_iterations(&self) -> u32 {
+        self.max_iterations
+    }
+
+    fn set_max_iterations(&mut self, max_iterations: u32) {
+        self.max_iterations = max_iterations;
+    }
+
+    fn set_periodicity_checking(&mut self, enabled: bool) {
+        self.periodicity_checking = enabled;
+    }
+
+    fn set_bailout(&mut self, bailout: Bailout) {
+        self.bailout = bailout;
+    }
+
+    fn orbit(&self, x: f64, y: f64) -> Option<Orbit> {
+        let c = Complex64::new(x, y);
+        Some(follow_orbit(
+            c,
+            self.max_iterations,
+            |z| self.bailout.bounded(z),
+            |z| powi(z, self.power) + c,
+        ))
+    }
+}
@@ -0,0 +1,80 @@
+use crate::fractal::Bailout;
+use crate::fractal::{escape, powi, EscapeTime, Fractal, SmoothIterations, DEFAULT_MAX_ITERATIONS};
+use crate::fractal::{follow_orbit, Orbit};
+use num_complex::Complex64;
+
+/// The Julia counterpart of the Multibrot fractal, `c` is fixed.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct MultibrotJulia {
+    c: Complex64,
+    power: u32,
+    max_iterations: u32,
+    periodicity_checking: bool,
+    bailout: Bailout,
+}
+
+impl MultibrotJulia {
+    pub fn new(re: f64, im: f64, power: u32) -> MultibrotJulia {
+        assert!(power >= 2, "multibrot power must be greater than one");
+        MultibrotJulia {
+            c: Complex64::new(re, im),
+            power,
+            max_iterations: DEFAULT_MAX_ITERATIONS,
+            periodicity_checking: false,
+            bailout: Bailout::default(),
+        }
+    }
+
+    pub fn power(&self) -> u32 {
+        self.power
+    }
+
+    fn escape(&self, x: f64, y: f64) -> (u32, Complex64) {
+        let z = Complex64::new(x, y);
+        escape(
+            z,
+            self.max_iterations,
+            self.periodicity_checking,
+            |z| self.bailout.bounded(z),
+            |z| powi(z, self.power) + self.c,
+        )
+    }
+}
+
+impl Fractal for MultibrotJulia {
+    fn iterations(&self, x: f64, y: f64) -> EscapeTime {
+        let (iterations, _) = self.escape(x, y);
+        EscapeTime { iterations, max_iterations: self.max_iterations }
+    }
+
+    fn smooth_iterations(&self, x: f64, y: f64) -> SmoothIterations {
+        let (iterations, z) = self.escape(x, y);
+        SmoothIterations::new(iterations, self.max_iterations, z, self.power as f64)
+    }
+
+    fn max_iterations(&self) -> u32 {
+        self.max_iterations
+    }
+
+    fn set_max_iterations(&mut self, max_iterations: u32) {
+        self.max_iterations = max_iterations;
+    }
+
+    fn set_periodicity_checking(&mut self, enabled: bool) {
+        self.periodicity_checking = enabled;
+    }
+
+    fn set_bailout(&mut self, bailout: Bailout) {
+        self.bailout = bailout;
+    }
+
+    fn orbit(&self, x: f64, y: f64) -> Option<Orbit> {
+        let z = Complex64::new(x, y);
+        Some(follow_orbit(
+            z,
+            self.max_iterations,
+            |z| self.bailout.bounded(z),
+            |z| powi(z, self.power) + self.c,
+        ))
+    }
+}
@@ -0,0 +1,193 @@
+use std::f64::consts::LN_2;
+
+use crate::fractal::{EscapeTime, Fractal, SmoothIterations};
+use num_complex::Complex64;
+
+/// The squared distance under which a point is considered to have reached a root.
+const TOLERANCE: f64 = 1e-12;
+
+/// Most points reach a root in a few iterations, a lower limit
+/// than the escape time fractals gives more contrast to the convergence times.
+pub const NEWTON_MAX_ITERATIONS: u32 = 32;
+
+/// The root a point converged to and the time it took to reach it.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Convergence {
+    /// The index of the root in `Newton::roots`, `None` if the point did not converge.
+    pub root: Option<usize>,
+    pub iterations: EscapeTime,
+    /// The fractional iteration count, useful to shade basins without visible bands.
+    pub smooth_iterations: f64,
+}
+
+/// The Newton-Raphson fractal of a polynomial,
+/// points are iterated with `z - p(z) / p'(z)` until they reach one of the roots.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Newton {
+    /// The coefficients of the polynomial, in ascending powers.
+    coefficients: Vec<Complex64>,
+    roots: Vec<Complex64>,
+    max_iterations: u32,
+}
+
+impl Newton {
+    /// Creates the fractal of the polynomial that has these roots.
+    pub fn from_roots(roots: Vec<Complex64>) -> Newton {
+        assert!(!roots.is_empty(), "the polynomial must have at least one root");
+
+        // expand the `(z - r0)(z - r1)...` product one root at a time
+        let mut coefficients = vec![Complex64::new(1.0, 0.0)];
+        for root in &roots {
+            let mut expanded = vec![Complex64::new(0.0, 0.0); coefficients.len() + 1];
+            for (i, coefficient) in coefficients.iter().enumerate() {
+                expanded[i + 1] += coefficient;
+                expanded[i] -= coefficient * root;
+            }
+            coefficients = expanded;
+        }
+
+        Newton { coefficients, roots, max_iterations: NEWTON_MAX_ITERATIONS }
+    }
+
+    /// Creates the fractal of the polynomial with these coefficients,
+    /// given in ascending powers, the roots are found numerically.
+    pub fn from_coefficients(coefficients: Vec<Complex64>) -> Newton {
+        let degree = coefficients.iter().rposition(|c| c.norm_sqr() != 0.0);
+        let degree =
+            degree.filter(|&d| d > 0).expect("the polynomial must be at least of degree one");
+
+        let mut coefficients = coefficients;
+        coefficients.truncate(degree + 1);
+        let roots = durand_kerner(&coefficients);
+
+        Newton { coefficients, roots, max_iterations: NEWTON_MAX_ITERATIONS }
+    }
+
+    pub fn roots(&self) -> &[Complex64] {
+        &self.roots
+    }
+
+    pub fn coefficients(&self) -> &[Complex64] {
+        &self.coefficients
+    }
+
+    pub fn degree(&self) -> usize {
+        self.coefficients.len() - 1
+    }
+
+    /// Iterates the point until it reaches a root of the polynomial.
+    pub fn convergence(&self, x: f64, y: f64) -> Convergence {
+        let mut z = Complex64::new(x, y);
+        let mut iterations = 0;
+
+        while iterations < self.max_iterations {
+            if let Some((root, distance)) = self.nearest_root(z) {
+                let smooth = smooth_convergence(iterations, distance);
+                let iterations = EscapeTime { iterations, max_iterations: self.max_iterations };
+                return Convergence { root: Some(root), iterations, smooth_iterations: smooth };
+            }
+
+            let (p, dp) = evaluate(&self.coefficients, z);
+            if dp.norm_sqr() == 0.0 {
+                break;
+            }
+
+            z -= p / dp;
+            iterations += 1;
+        }
+
+        let iterations =
+            EscapeTime { iterations: self.max_iterations, max_iterations: self.max_iterations };
+        Convergence { root: None, iterations, smooth_iterations: self.max_iterations as f64 }
+    }
+
+    /// Returns the index of the root `z` is close enough to and the squared distance to it.
+    fn nearest_root(&self, z: Complex64) -> Option<(usize, f64)> {
+        self.roots
+            .iter()
+            .map(|r| (z - r).norm_sqr())
+            .enumerate()
+            .find(|&(_, distance)| distance < TOLERANCE)
+    }
+}
+
+/// Newton's method converges quadratically, the logarithm of the distance
+/// to the root doubles at each iteration, we use that to interpolate
+/// between the integer iteration counts.
+fn smooth_convergence(iterations: u32, distance: f64) -> f64 {
+    if distance == 0.0 {
+        return iterations as f64;
+    }
+    let ratio = distance.ln() / TOLERANCE.ln();
+    iterations as f64 + 1.0 - (ratio.ln() / LN_2).clamp(0.0, 1.0)
+}
+
+/// Evaluates the polynomial and its derivative using the Horner method.
+fn evaluate(coefficients: &[Complex64], z: Complex64) -> (Complex64, Complex64) {
+    let mut p = Complex64::new(0.0, 0.0);
+    let mut dp = Complex64::new(0.0, 0.0);
+
+    for coefficient in coefficients.iter().rev() {
+        dp = dp * z + p;
+        p = p * z + coefficient;
+    }
+
+    (p, dp)
+}
+
+/// Finds all the roots of the polynomial at the same time
+/// using the Durand-Kerner (Weierstrass) method.
+fn durand_kerner(coefficients: &[Complex64]) -> Vec<Complex64> {
+    let leading = coefficients[coefficients.len() - 1];
+    let monic: Vec<_> = coefficients.iter().map(|c| c / leading).collect();
+    let degree = monic.len() - 1;
+
+    // the initial guesses must be neither real nor roots of unity
+    let seed = Complex64::new(0.4, 0.9);
+    let mut roots: Vec<_> = (0..degree as u32).map(|i| seed.powf(i as f64)).collect();
+
+    for _ in 0..500 {
+        let mut moved = 0.0f64;
+
+        for i in 0..degree {
+            let (p, _) = evaluate(&monic, roots[i]);
+            let mut denominator = Complex64::new(1.0, 0.0);
+            for j in (0..degree).filter(|&j| j != i) {
+                denominator *= roots[i] - roots[j];
+            }
+            if denominator.norm_sqr() == 0.0 {
+                continue;
+            }
+
+            let delta = p / denominator;
+            roots[i] -= delta;
+            moved = moved.max(delta.norm_sqr());
+        }
+
+        if moved < TOLERANCE * TOLERANCE {
+            break;
+        }
+    }
+
+    roots
+}
+
+impl Fractal for Newton {
+    fn iterations(&self, x: f64, y: f64) -> EscapeTime {
+        self.convergence(x, y).iterations
+    }
+
+    fn smooth_iterations(&self, x: f64, y: f64) -> SmoothIterations {
+        let convergence = self.convergence(x, y);
+        let value = convergence.smooth_iterations / self.max_iterations as f64;
+        SmoothIterations { value: value.clamp(0.0, 1.0), escaped: convergence.root.is_some() }
+    }
+
+    fn max_iterations(&self) -> u32 {
+        self.max_iterations
+    }
+
+    fn set_max_iterations(&mut self, max_iterations: u32) {
+        self.max_iterations = max_iterations;
+    }
+}
@@ -0,0 +1,139 @@
+use crate::fractal::ConvergenceBailout;
+use crate::fractal::{powi, EscapeTime, Fractal, SmoothIterations, DEFAULT_MAX_ITERATIONS};
+use num_complex::Complex64;
+
+/// The Nova fractal, a relaxed Newton-Raphson iteration of `z^power - 1` to which `c` is added,
+/// iterates `z - relaxation·(z^power - 1) / (power·z^(power - 1)) + c`.
+///
+/// `c` is the point and the orbit starts at `z = 1`, a root of the polynomial.
+/// The points are iterated until their orbit converges.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Nova {
+    power: u32,
+    relaxation: f64,
+    max_iterations: u32,
+    convergence: ConvergenceBailout,
+}
+
+impl Nova {
+    pub fn new(power: u32, relaxation: f64) -> Nova {
+        assert!(power >= 2, "nova power must be greater than one");
+        Nova {
+            power,
+            relaxation,
+            max_iterations: DEFAULT_MAX_ITERATIONS,
+            convergence: ConvergenceBailout::default(),
+        }
+    }
+
+    pub fn power(&self) -> u32 {
+        self.power
+    }
+
+    /// The factor applied to the Newton step, `1` is the usual Newton method.
+    pub fn relaxation(&self) -> f64 {
+        self.relaxation
+    }
+}
+
+/// Returns the next value of a Nova orbit.
+pub(crate) fn nova_step(z: Complex64, c: Complex64, power: u32, relaxation: f64) -> Complex64 {
+    let derivative = powi(z, power - 1);
+    let polynomial = derivative * z - Complex64::new(1.0, 0.0);
+    z - (polynomial / derivative.scale(power as f64)).scale(relaxation) + c
+}
+
+/// Iterates a Nova orbit until it converges, returns the iteration count
+/// and the smoothed count, `None` if the orbit did not converge.
+pub(crate) fn nova_convergence(
+    mut z: Complex64,
+    c: Complex64,
+    power: u32,
+    relaxation: f64,
+    max_iterations: u32,
+    convergence: &ConvergenceBailout,
+) -> (u32, Option<f64>) {
+    let tolerance = convergence.tolerance() * convergence.tolerance();
+    let mut last_distance = f64::INFINITY;
+
+    for iterations in 0..max_iterations {
+        let previous = z;
+        z = nova_step(z, c, power, relaxation);
+
+        // the orbit went through the pole of the Newton step
+        if !z.re.is_finite() || !z.im.is_finite() {
+            break;
+        }
+
+        let distance = convergence.distance(previous, z);
+        if distance <= tolerance {
+            // the logarithm of the distance is interpolated over the last iteration
+            // to find when it crossed the tolerance, the relaxation makes the
+            // convergence linear instead of quadratic so both are handled
+            let (last, current) = (last_distance.ln(), distance.ln());
+            let crossing = (last - tolerance.ln()) / (last - current);
+            let smooth = if crossing.is_finite() {
+                iterations as f64 + crossing.clamp(0.0, 1.0)
+            } else {
+                iterations as f64
+            };
+            return (iterations, Some(smooth));
+        }
+        last_distance = distance;
+    }
+
+    (max_iterations, None)
+}
+
+/// The smooth iterations of a Nova orbit, converged orbits are considered escaped.
+pub(crate) fn nova_smooth_iterations(smooth: Option<f64>, max_iterations: u32) -> SmoothIterations {
+    match smooth {
+        Some(smooth) => {
+            let value = (smooth / max_iterations as f64).clamp(0.0, 1.0);
+            SmoothIterations { value, escaped: true }
+        }
+        None => SmoothIterations { value: 1.0, escaped: false },
+    }
+}
+
+impl Fractal for Nova {
+    fn iterations(&self, x: f64, y: f64) -> EscapeTime {
+        let z = Complex64::new(1.0, 0.0);
+        let c = Complex64::new(x, y);
+        let (iterations, _) = nova_convergence(
+            z,
+            c,
+            self.power,
+            self.relaxation,
+            self.max_iterations,
+            &self.convergence,
+        );
+        EscapeTime { iterations, max_iterations: self.max_iterations }
+    }
+
+    fn smooth_iterations(&self, x: f64, y: f64) -> SmoothIterations {
+        let z = Complex64::new(1.0, 0.0);
+        let c = Complex64::new(x, y);
+        let (_, smooth) = nova_convergence(
+            z,
+            c,
+            self.power,
+            self.relaxation,
+            self.max_iterations,
+            &self.convergence,
+        );
+        nova_smooth_iterations(smooth, self.max_iterations)
+    }
+
+    fn max_iterations(&self) -> u32 {
+        self.max_iterations
+    }
+
+    fn set_max_iterations(&mut self, max_iterations: u32) {
+        self.max_iterations = max_iterations;
+    }
+
+    fn set_convergence_bailout(&mut self, convergence: ConvergenceBailout) {
+        self.convergence = convergence;
+    }
+}
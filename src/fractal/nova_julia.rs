@@ -0,0 +1,76 @@
+use crate::fractal::ConvergenceBailout;
+use crate::fractal::{nova_convergence, nova_smooth_iterations};
+use crate::fractal::{EscapeTime, Fractal, SmoothIterations, DEFAULT_MAX_ITERATIONS};
+use num_complex::Complex64;
+
+/// The Julia counterpart of the Nova fractal, `c` is fixed and the orbit starts at the point.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct NovaJulia {
+    c: Complex64,
+    power: u32,
+    relaxation: f64,
+    max_iterations: u32,
+    convergence: ConvergenceBailout,
+}
+
+impl NovaJulia {
+    pub fn new(re: f64, im: f64, power: u32, relaxation: f64) -> NovaJulia {
+        assert!(power >= 2, "nova power must be greater than one");
+        NovaJulia {
+            c: Complex64::new(re, im),
+            power,
+            relaxation,
+            max_iterations: DEFAULT_MAX_ITERATIONS,
+            convergence: ConvergenceBailout::default(),
+        }
+    }
+
+    pub fn power(&self) -> u32 {
+        self.power
+    }
+
+    /// The factor applied to the Newton step, `1` is the usual Newton method.
+    pub fn relaxation(&self) -> f64 {
+        self.relaxation
+    }
+}
+
+impl Fractal for NovaJulia {
+    fn iterations(&self, x: f64, y: f64) -> EscapeTime {
+        let z = Complex64::new(x, y);
+        let (iterations, _) = nova_convergence(
+            z,
+            self.c,
+            self.power,
+            self.relaxation,
+            self.max_iterations,
+            &self.convergence,
+        );
+        EscapeTime { iterations, max_iterations: self.max_iterations }
+    }
+
+    fn smooth_iterations(&self, x: f64, y: f64) -> SmoothIterations {
+        let z = Complex64::new(x, y);
+        let (_, smooth) = nova_convergence(
+            z,
+            self.c,
+            self.power,
+            self.relaxation,
+            self.max_iterations,
+            &self.convergence,
+        );
+        nova_smooth_iterations(smooth, self.max_iterations)
+    }
+
+    fn max_iterations(&self) -> u32 {
+        self.max_iterations
+    }
+
+    fn set_max_iterations(&mut self, max_iterations: u32) {
+        self.max_iterations = max_iterations;
+    }
+
+    fn set_convergence_bailout(&mut self, convergence: ConvergenceBailout) {
+        self.convergence = convergence;
+    }
+}
@@ -0,0 +1,89 @@
+use num_complex::Complex64;
+use rand_derive::Rand;
+
+use crate::fractal::EscapeTime;
+
+/// The longest cycle searched for in the orbits that did not escape.
+const MAX_PERIOD: u32 = 1024;
+
+/// The squared distance under which two values of an orbit are considered equal.
+const CYCLE_TOLERANCE: f64 = 1e-16;
+
+/// What the orbit of a point looks like, not only how long it took to escape,
+/// useful to color the points that never escape.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Orbit {
+    pub iterations: EscapeTime,
+    /// The last value of the orbit.
+    pub last: Complex64,
+    /// The period of the cycle the orbit was attracted to, `None` if the point escaped
+    /// or if the orbit did not settle on a cycle before the iteration limit.
+    pub period: Option<u32>,
+    /// An estimation of the distance from the point to the fractal boundary,
+    /// only given for the points inside the fractal by the fractals that support it.
+    pub interior_distance: Option<f64>,
+}
+
+/// Iterates `z` like `escape` does and searches for the cycle the orbit fell into
+/// when it did not escape.
+pub(crate) fn follow_orbit<B, S>(z: Complex64, max_iterations: u32, bounded: B, step: S) -> Orbit
+where
+    B: Fn(Complex64) -> bool,
+    S: Fn(Complex64) -> Complex64,
+{
+    let mut z = z;
+    let mut iterations = 0;
+
+    while bounded(z) && iterations < max_iterations {
+        z = step(z);
+        iterations += 1;
+    }
+
+    let iterations = EscapeTime { iterations, max_iterations };
+    let period = if iterations.escaped() { None } else { detect_period(z, &step) };
+
+    Orbit { iterations, last: z, period, interior_distance: None }
+}
+
+/// Returns the smallest number of steps that bring `z` back to itself,
+/// `z` must already be on the cycle, or close enough to it.
+pub(crate) fn detect_period<S>(z: Complex64, step: S) -> Option<u32>
+where
+    S: Fn(Complex64) -> Complex64,
+{
+    let mut w = z;
+    for period in 1..=MAX_PERIOD {
+        w = step(w);
+        if (w - z).norm_sqr() < CYCLE_TOLERANCE {
+            return Some(period);
+        }
+    }
+    None
+}
+
+/// What is kept from an `Orbit` to color the points inside the fractal.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Rand)]
+pub enum InteriorColoring {
+    /// The magnitude of the last value of the orbit.
+    FinalMagnitude,
+    /// The period of the cycle the orbit was attracted to.
+    Period,
+    /// The estimated distance to the fractal boundary.
+    Distance,
+}
+
+impl InteriorColoring {
+    /// Returns the value of the orbit used for coloring, it is not normalized,
+    /// `None` if the orbit escaped or does not provide this information.
+    pub fn value(&self, orbit: &Orbit) -> Option<f64> {
+        if orbit.iterations.escaped() {
+            return None;
+        }
+
+        match *self {
+            InteriorColoring::FinalMagnitude => Some(orbit.last.norm()),
+            InteriorColoring::Period => orbit.period.map(|p| p as f64),
+            InteriorColoring::Distance => orbit.interior_distance,
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use std::f64::consts::PI;
+
+use num_complex::Complex64;
+use rand::{Rand, Rng};
+use rand_derive::Rand;
+
+/// A shape the orbit of a point is compared to,
+/// the orbit is "trapped" by the iteration that comes the closest to it.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum OrbitTrap {
+    Point(Complex64),
+    /// The infinite line going through `origin` with the given `angle` in radians.
+    Line {
+        origin: Complex64,
+        angle: f64,
+    },
+    /// The horizontal and vertical lines that cross at a point.
+    Cross(Complex64),
+    Circle {
+        center: Complex64,
+        radius: f64,
+    },
+}
+
+impl OrbitTrap {
+    /// Returns the distance from `z` to the shape of the trap.
+    pub fn distance(&self, z: Complex64) -> f64 {
+        match *self {
+            OrbitTrap::Point(point) => (z - point).norm(),
+            OrbitTrap::Line { origin, angle } => {
+                let Complex64 { re, im } = z - origin;
+                (re * angle.sin() - im * angle.cos()).abs()
+            }
+            OrbitTrap::Cross(center) => {
+                let Complex64 { re, im } = z - center;
+                re.abs().min(im.abs())
+            }
+            OrbitTrap::Circle { center, radius } => ((z - center).norm() - radius).abs(),
+        }
+    }
+}
+
+impl Rand for OrbitTrap {
+    /// Generates a trap that lies around the origin where most orbits wander.
+    fn rand<R: Rng>(rng: &mut R) -> OrbitTrap {
+        let center = Complex64::new(rng.gen_range(-1.0, 1.0), rng.gen_range(-1.0, 1.0));
+
+        match rng.gen_range(0, 4) {
+            0 => OrbitTrap::Point(center),
+            1 => OrbitTrap::Line { origin: center, angle: rng.gen_range(0.0, PI) },
+            2 => OrbitTrap::Cross(center),
+            _ => OrbitTrap::Circle { center, radius: rng.gen_range(0.1, 1.0) },
+        }
+    }
+}
+
+/// How close the orbit of a point came to a trap.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TrapResult {
+    /// The minimum distance between the orbit and the trap.
+    pub distance: f64,
+    /// The index of the iteration that came the closest to the trap.
+    pub iteration: u32,
+    /// The number of iterations the orbit was followed for.
+    pub iterations: u32,
+}
+
+/// Iterates `z` like `escape` does and compares every value of the orbit to the `trap`.
+pub(crate) fn trap_orbit<B, S>(
+    mut z: Complex64,
+    max_iterations: u32,
+    trap: &OrbitTrap,
+    bounded: B,
+    step: S,
+) -> TrapResult
+where
+    B: Fn(Complex64) -> bool,
+    S: Fn(Complex64) -> Complex64,
+{
+    let mut result = TrapResult { distance: trap.distance(z), iteration: 0, iterations: 0 };
+
+    while bounded(z) && result.iterations < max_iterations {
+        z = step(z);
+        result.iterations += 1;
+
+        let distance = trap.distance(z);
+        if distance < result.distance {
+            result.distance = distance;
+            result.iteration = result.iterations;
+        }
+    }
+
+    result
+}
+
+/// What is kept from a `TrapResult` to color a point.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Rand)]
+pub enum TrapColoring {
+    /// The minimum distance between the orbit and the trap.
+    Distance,
+    /// The moment, relative to the length of the orbit, the trap was the closest.
+    Iteration,
+}
+
+impl TrapColoring {
+    /// Returns the value of the result normalized in `[0, 1]`.
+    pub fn value(&self, result: &TrapResult) -> f64 {
+        match *self {
+            TrapColoring::Distance => 1.0 - (-4.0 * result.distance).exp(),
+            TrapColoring::Iteration if result.iterations == 0 => 0.0,
+            TrapColoring::Iteration => result.iteration as f64 / result.iterations as f64,
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use crate::fractal::mandelbrot::legacy_bounded;
+use crate::fractal::{Bailout, EscapeTime, SmoothIterations};
+use crate::precision::{BigFixed, PrecisePoint};
+use num_complex::Complex64;
+
+/// The orbit of a single point of the Mandelbrot fractal computed in high precision,
+/// the orbits of the points around it are deduced from this one.
+#[derive(Debug, Clone)]
+pub struct ReferenceOrbit {
+    center: PrecisePoint,
+    /// The values of the orbit rounded to `f64`, starting from zero.
+    orbit: Vec<Complex64>,
+}
+
+impl ReferenceOrbit {
+    pub fn new(center: PrecisePoint, max_iterations: u32) -> ReferenceOrbit {
+        let PrecisePoint { x: cx, y: cy } = center;
+        let (mut zx, mut zy) = (BigFixed::zero(), BigFixed::zero());
+
+        let mut orbit = Vec::with_capacity(max_iterations as usize + 2);
+        orbit.push(Complex64::new(0.0, 0.0));
+
+        for _ in 0..=max_iterations {
+            let xy = zx * zy;
+            zx = zx * zx - zy * zy + cx;
+            zy = xy + xy + cy;
+
+            let z = Complex64::new(zx.to_f64(), zy.to_f64());
+            orbit.push(z);
+
+            // the pixels will rebase their orbit on the start of this one
+            if z.norm_sqr() > 4.0 {
+                break;
+            }
+        }
+
+        ReferenceOrbit { center, orbit }
+    }
+
+    pub fn center(&self) -> &PrecisePoint {
+        &self.center
+    }
+
+    pub fn len(&self) -> usize {
+        self.orbit.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.orbit.is_empty()
+    }
+}
+
+/// Renders the Mandelbrot fractal at zooms where `f64` coordinates
+/// can no more distinguish neighbouring pixels.
+///
+/// Only the reference orbit is computed in high precision, every pixel iterates
+/// its small difference with the reference (`δ' = 2Zδ + δ² + δc`) using floats,
+/// which works until the pixel offsets underflow a `f64`, around `1e-300`.
+///
+/// Glitches are avoided by rebasing the difference on the start of the reference orbit
+/// when the pixel orbit gets closer to zero than the difference itself,
+/// https://fractalforums.org/f/28/t/4360
+#[derive(Debug, Clone)]
+pub struct PerturbedMandelbrot {
+    reference: ReferenceOrbit,
+    max_iterations: u32,
+    bailout: Bailout,
+}
+
+impl PerturbedMandelbrot {
+    pub fn new(center: PrecisePoint, max_iterations: u32) -> PerturbedMandelbrot {
+        PerturbedMandelbrot {
+            reference: ReferenceOrbit::new(center, max_iterations),
+            max_iterations,
+            bailout: Bailout::default(),
+        }
+    }
+
+    /// Changes the test that decides whether an orbit escaped,
+    /// the legacy mode tests the same way `Mandelbrot` does.
+    pub fn set_bailout(&mut self, bailout: Bailout) {
+        self.bailout = bailout;
+    }
+
+    pub fn reference(&self) -> &ReferenceOrbit {
+        &self.reference
+    }
+
+    pub fn max_iterations(&self) -> u32 {
+        self.max_iterations
+    }
+
+    /// Iterates the point at the given offset from the reference center,
+    /// the same way `Mandelbrot` does for absolute coordinates.
+    fn escape(&self, dx: f64, dy: f64) -> (u32, Complex64) {
+        let orbit = &self.reference.orbit;
+        let dc = Complex64::new(dx, dy);
+
+        let mut m = 1;
+        let mut delta = dc;
+        let mut z = orbit[m] + delta;
+        let mut iterations = 0;
+
+        while self.bailout.bounded_with_legacy(z, legacy_bounded)
+            && iterations < self.max_iterations
+        {
+            if m == orbit.len() - 1 || z.norm_sqr() < delta.norm_sqr() {
+                delta = z;
+                m = 0;
+            }
+
+            delta = orbit[m] * (delta + delta) + delta * delta + dc;
+            m += 1;
+            z = orbit[m] + delta;
+            iterations += 1;
+        }
+
+        (iterations, z)
+    }
+
+    /// Returns the escape time of the point at the given offset from the reference center.
+    pub fn iterations(&self, dx: f64, dy: f64) -> EscapeTime {
+        let (iterations, _) = self.escape(dx, dy);
+        EscapeTime { iterations, max_iterations: self.max_iterations }
+    }
+
+    /// Returns the continuous escape value of the point at the given offset from the reference center.
+    pub fn smooth_iterations(&self, dx: f64, dy: f64) -> SmoothIterations {
+        let (iterations, z) = self.escape(dx, dy);
+        SmoothIterations::new(iterations, self.max_iterations, z, 2.0)
+    }
+}
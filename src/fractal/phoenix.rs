@@ -0,0 +1,70 @@
+use crate::fractal::Bailout;
+use crate::fractal::{EscapeTime, Fractal, SmoothIterations, DEFAULT_MAX_ITERATIONS};
+use num_complex::Complex64;
+
+/// The Phoenix fractal, `z' = z² + c + p·z₋₁` where `z₋₁` is the value
+/// that preceded `z`, `c` and `p` are fixed like the domain of a Julia fractal.
+///
+/// The orbit starts at the point with a previous value of zero.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Phoenix {
+    c: Complex64,
+    p: Complex64,
+    max_iterations: u32,
+    bailout: Bailout,
+}
+
+impl Phoenix {
+    pub fn new(c: Complex64, p: Complex64) -> Phoenix {
+        Phoenix { c, p, max_iterations: DEFAULT_MAX_ITERATIONS, bailout: Bailout::default() }
+    }
+
+    pub fn c(&self) -> Complex64 {
+        self.c
+    }
+
+    /// The weight of the previous value of the orbit.
+    pub fn p(&self) -> Complex64 {
+        self.p
+    }
+
+    fn escape(&self, x: f64, y: f64) -> (u32, Complex64) {
+        let mut z = Complex64::new(x, y);
+        let mut previous = Complex64::new(0.0, 0.0);
+        let mut iterations = 0;
+
+        while self.bailout.bounded(z) && iterations < self.max_iterations {
+            let next = z * z + self.c + self.p * previous;
+            previous = z;
+            z = next;
+            iterations += 1;
+        }
+
+        (iterations, z)
+    }
+}
+
+impl Fractal for Phoenix {
+    fn iterations(&self, x: f64, y: f64) -> EscapeTime {
+        let (iterations, _) = self.escape(x, y);
+        EscapeTime { iterations, max_iterations: self.max_iterations }
+    }
+
+    fn smooth_iterations(&self, x: f64, y: f64) -> SmoothIterations {
+        // the squared term dominates once the orbit escaped
+        let (iterations, z) = self.escape(x, y);
+        SmoothIterations::new(iterations, self.max_iterations, z, 2.0)
+    }
+
+    fn max_iterations(&self) -> u32 {
+        self.max_iterations
+    }
+
+    fn set_max_iterations(&mut self, max_iterations: u32) {
+        self.max_iterations = max_iterations;
+    }
+
+    fn set_bailout(&mut self, bailout: Bailout) {
+        self.bailout = bailout;
+    }
+}
@@ -0,0 +1,47 @@
+use crate::fractal3d::Vector3;
+
+/// A pinhole camera placed in the 3D world, the vertical axis is `y`.
+///
+/// When the camera looks straight up or down the top of the screen points toward `-z`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Camera3d {
+    pub position: Vector3,
+    pub look_at: Vector3,
+    /// The vertical field of view in degrees.
+    pub fov: f64,
+}
+
+impl Camera3d {
+    pub fn new(position: Vector3, look_at: Vector3, fov: f64) -> Camera3d {
+        assert!(fov > 0.0 && fov < 180.0, "fov must be in ]0, 180[ degrees");
+        assert!(position != look_at, "the camera can not look at its own position");
+        Camera3d { position, look_at, fov }
+    }
+
+    /// Returns the normalized direction of the ray that goes through the center
+    /// of the pixel, `point` is in screen coordinates (`[0, width[`).
+    pub fn ray_direction(&self, point: [f64; 2], dimensions: (u32, u32)) -> Vector3 {
+        let (width, height) = dimensions;
+        let [x, y] = point;
+
+        let forward = (self.look_at - self.position).normalize();
+
+        // the vertical axis gives no horizontal direction when looking along it
+        let right = forward.cross(Vector3::new(0.0, 1.0, 0.0));
+        let right = if right.length() > 1e-9 {
+            right.normalize()
+        } else {
+            forward.cross(Vector3::new(0.0, 0.0, -1.0)).normalize()
+        };
+        let up = right.cross(forward);
+
+        // the screen is at a distance of one from the camera
+        let half_height = (self.fov.to_radians() / 2.0).tan();
+        let half_width = half_height * width as f64 / height as f64;
+
+        let sx = ((x + 0.5) * 2.0 / width as f64 - 1.0) * half_width;
+        let sy = (1.0 - (y + 0.5) * 2.0 / height as f64) * half_height;
+
+        (forward + right * sx + up * sy).normalize()
+    }
+}
@@ -0,0 +1,89 @@
+use crate::fractal3d::{Fractal3d, Vector3};
+
+/// The Mandelbox, the points are folded in a box then in a sphere
+/// and scaled at each iteration.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Mandelbox {
+    scale: f64,
+    min_radius: f64,
+    fixed_radius: f64,
+    folding_limit: f64,
+    max_iterations: u32,
+}
+
+impl Mandelbox {
+    pub fn new(scale: f64) -> Mandelbox {
+        Mandelbox {
+            scale,
+            min_radius: 0.5,
+            fixed_radius: 1.0,
+            folding_limit: 1.0,
+            max_iterations: 16,
+        }
+    }
+
+    pub fn scale(&self) -> f64 {
+        self.scale
+    }
+
+    /// The radii of the sphere fold, the points inside the min radius are scaled
+    /// by a constant, the ones between both radii are inverted.
+    pub fn radii(&mut self, min_radius: f64, fixed_radius: f64) -> &mut Self {
+        assert!(
+            min_radius > 0.0 && min_radius < fixed_radius,
+            "min radius must be in ]0, fixed radius["
+        );
+        self.min_radius = min_radius;
+        self.fixed_radius = fixed_radius;
+        self
+    }
+
+    /// The half side of the box fold.
+    pub fn folding_limit(&mut self, folding_limit: f64) -> &mut Self {
+        self.folding_limit = folding_limit;
+        self
+    }
+
+    pub fn max_iterations(&mut self, max_iterations: u32) -> &mut Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+}
+
+impl Default for Mandelbox {
+    /// The scale of -1.5 which fits in a box of side 4.
+    fn default() -> Mandelbox {
+        Mandelbox::new(-1.5)
+    }
+}
+
+impl Fractal3d for Mandelbox {
+    fn distance_estimate(&self, point: Vector3) -> f64 {
+        let limit = self.folding_limit;
+        let min_radius2 = self.min_radius * self.min_radius;
+        let fixed_radius2 = self.fixed_radius * self.fixed_radius;
+
+        let box_fold = |v: f64| v.clamp(-limit, limit) * 2.0 - v;
+
+        let mut z = point;
+        let mut dr = 1.0;
+
+        for _ in 0..self.max_iterations {
+            z = Vector3::new(box_fold(z.x), box_fold(z.y), box_fold(z.z));
+
+            let r2 = z.dot(z);
+            let factor = if r2 < min_radius2 {
+                fixed_radius2 / min_radius2
+            } else if r2 < fixed_radius2 {
+                fixed_radius2 / r2
+            } else {
+                1.0
+            };
+
+            z = z * (factor * self.scale) + point;
+            dr = dr * factor * self.scale.abs() + 1.0;
+        }
+
+        z.length() / dr.abs()
+    }
+}
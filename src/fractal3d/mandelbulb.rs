@@ -0,0 +1,60 @@
+use crate::fractal3d::{Fractal3d, Vector3};
+
+/// The Mandelbulb, the Mandelbrot fractal extended to 3D by raising
+/// the points to a power in spherical coordinates.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Mandelbulb {
+    power: f64,
+    max_iterations: u32,
+}
+
+impl Mandelbulb {
+    pub fn new(power: f64) -> Mandelbulb {
+        assert!(power >= 2.0, "mandelbulb power must be at least two");
+        Mandelbulb { power, max_iterations: 16 }
+    }
+
+    pub fn power(&self) -> f64 {
+        self.power
+    }
+
+    pub fn max_iterations(&mut self, max_iterations: u32) -> &mut Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+}
+
+impl Default for Mandelbulb {
+    /// The usual power of 8 which gives the most famous shape.
+    fn default() -> Mandelbulb {
+        Mandelbulb::new(8.0)
+    }
+}
+
+impl Fractal3d for Mandelbulb {
+    fn distance_estimate(&self, point: Vector3) -> f64 {
+        let mut z = point;
+        let mut dr = 1.0;
+        let mut r = 0.0;
+
+        for _ in 0..self.max_iterations {
+            r = z.length();
+            if r > 2.0 {
+                break;
+            }
+
+            // the derivative of `z^power + c` tracks how fast the orbit moves away
+            dr = r.powf(self.power - 1.0) * self.power * dr + 1.0;
+
+            let theta = (z.z / r).acos() * self.power;
+            let phi = z.y.atan2(z.x) * self.power;
+            let zr = r.powf(self.power);
+
+            let direction =
+                Vector3::new(theta.sin() * phi.cos(), theta.sin() * phi.sin(), theta.cos());
+            z = direction * zr + point;
+        }
+
+        0.5 * r.ln() * r / dr
+    }
+}
@@ -0,0 +1,18 @@
+mod camera3d;
+mod mandelbox;
+mod mandelbulb;
+mod ray_marcher;
+mod vector3;
+
+pub use self::camera3d::Camera3d;
+pub use self::mandelbox::Mandelbox;
+pub use self::mandelbulb::Mandelbulb;
+pub use self::ray_marcher::RayMarcher;
+pub use self::vector3::Vector3;
+
+/// A 3D fractal that can be ray marched.
+pub trait Fractal3d {
+    /// Returns a lower bound of the distance from the point to the fractal surface,
+    /// the rays can move this far without going through the surface.
+    fn distance_estimate(&self, point: Vector3) -> f64;
+}
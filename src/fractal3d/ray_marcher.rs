@@ -0,0 +1,211 @@
+use image::RgbImage;
+use palette::rgb::LinSrgb;
+use rayon::prelude::*;
+
+use crate::fractal3d::{Camera3d, Fractal3d, Vector3};
+
+/// The distance at which the rays give up, the fractals fit in a much smaller box.
+const MAX_DISTANCE: f64 = 100.0;
+
+/// The distance along the normal of the samples used for the ambient occlusion.
+const OCCLUSION_STEP: f64 = 0.02;
+
+/// Renders the 3D fractals by marching the rays of the camera pixels,
+/// each ray moves forward by the distance estimate of its current point
+/// until it is close enough to the surface.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RayMarcher {
+    max_steps: u32,
+    light: Vector3,
+    ambient_occlusion: bool,
+    /// The hardness of the soft shadows, `None` disables the shadows.
+    shadows: Option<f64>,
+    color: LinSrgb,
+    background: LinSrgb,
+}
+
+impl RayMarcher {
+    pub fn new() -> RayMarcher {
+        RayMarcher {
+            max_steps: 256,
+            light: Vector3::new(0.577, 0.577, -0.577),
+            ambient_occlusion: true,
+            shadows: Some(16.0),
+            color: LinSrgb::new(1.0, 0.667, 0.0),
+            background: LinSrgb::new(0.0, 0.027, 0.392),
+        }
+    }
+
+    /// The number of steps after which a ray is considered to miss the fractal.
+    pub fn max_steps(&mut self, max_steps: u32) -> &mut Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    /// The direction toward the light, the light is infinitely far away.
+    pub fn light(&mut self, direction: Vector3) -> &mut Self {
+        self.light = direction.normalize();
+        self
+    }
+
+    /// Darkens the creases the light hardly reaches.
+    pub fn ambient_occlusion(&mut self, enabled: bool) -> &mut Self {
+        self.ambient_occlusion = enabled;
+        self
+    }
+
+    /// Casts shadows with penumbras, the greater the hardness the sharper the shadows.
+    pub fn soft_shadows(&mut self, hardness: Option<f64>) -> &mut Self {
+        if let Some(hardness) = hardness {
+            assert!(hardness > 0.0, "shadow hardness must be positive");
+        }
+        self.shadows = hardness;
+        self
+    }
+
+    pub fn colors(&mut self, color: LinSrgb, background: LinSrgb) -> &mut Self {
+        self.color = color;
+        self.background = background;
+        self
+    }
+
+    /// Renders the fractal seen by the camera.
+    pub fn render<F>(&self, fractal: &F, camera: &Camera3d, dimensions: (u32, u32)) -> RgbImage
+    where
+        F: Fractal3d + Sync,
+    {
+        let (width, height) = dimensions;
+
+        // the surface is hit when the distance gets under the size of a pixel
+        let pixel_angle = 2.0 * (camera.fov.to_radians() / 2.0).tan() / height as f64;
+
+        let mut image = RgbImage::new(width, height);
+        image.par_chunks_mut(3).enumerate().for_each(|(i, p)| {
+            let (x, y) = (i as u32 % width, i as u32 / width);
+            let direction = camera.ray_direction([x as f64, y as f64], dimensions);
+
+            let color = match self.march(fractal, camera.position, direction, pixel_angle) {
+                Some(hit) => self.shade(fractal, hit, direction, pixel_angle),
+                None => self.background,
+            };
+
+            let data: [u8; 3] = color.into_pixel();
+            p.copy_from_slice(&data);
+        });
+
+        image
+    }
+
+    /// Returns the point where the ray hits the surface, if it does.
+    fn march<F: Fractal3d>(
+        &self,
+        fractal: &F,
+        origin: Vector3,
+        direction: Vector3,
+        pixel_angle: f64,
+    ) -> Option<Vector3> {
+        let mut t = 0.0;
+
+        for _ in 0..self.max_steps {
+            let point = origin + direction * t;
+            let distance = fractal.distance_estimate(point);
+
+            if distance < t * pixel_angle {
+                return Some(point);
+            }
+
+            t += distance;
+            if t > MAX_DISTANCE || !t.is_finite() {
+                break;
+            }
+        }
+
+        None
+    }
+
+    fn shade<F: Fractal3d>(
+        &self,
+        fractal: &F,
+        point: Vector3,
+        direction: Vector3,
+        pixel_angle: f64,
+    ) -> LinSrgb {
+        let epsilon = (point.length() * pixel_angle).max(1e-6);
+        let normal = normal(fractal, point, epsilon);
+
+        // starts slightly off the surface to not hit it right away
+        let origin = point + normal * (epsilon * 2.0);
+
+        let diffuse = normal.dot(self.light).max(0.0);
+        let shadow = match self.shadows {
+            Some(hardness) if diffuse > 0.0 => self.soft_shadow(fractal, origin, hardness),
+            _ => 1.0,
+        };
+        let occlusion =
+            if self.ambient_occlusion { ambient_occlusion(fractal, point, normal) } else { 1.0 };
+
+        // the back faces get a bit of light from the side of the viewer
+        let back = normal.dot(-direction).max(0.0) * 0.15;
+        let light = (diffuse * shadow * 0.85 + (0.15 + back) * occlusion) as f32;
+
+        let LinSrgb { red, green, blue, .. } = self.color;
+        LinSrgb::new((red * light).min(1.0), (green * light).min(1.0), (blue * light).min(1.0))
+    }
+
+    /// Marches toward the light, the closer the ray passes by the surface
+    /// the darker the penumbra.
+    ///
+    /// http://iquilezles.org/www/articles/rmshadows/rmshadows.htm
+    fn soft_shadow<F: Fractal3d>(&self, fractal: &F, origin: Vector3, hardness: f64) -> f64 {
+        let mut shade = 1.0f64;
+        let mut t = 0.01;
+
+        for _ in 0..self.max_steps {
+            let distance = fractal.distance_estimate(origin + self.light * t);
+            if distance < 1e-5 {
+                return 0.0;
+            }
+
+            shade = shade.min(hardness * distance / t);
+            t += distance;
+            if t > MAX_DISTANCE {
+                break;
+            }
+        }
+
+        shade.clamp(0.0, 1.0)
+    }
+}
+
+impl Default for RayMarcher {
+    fn default() -> RayMarcher {
+        RayMarcher::new()
+    }
+}
+
+/// Returns the normal of the surface using the gradient of the distance estimate.
+fn normal<F: Fractal3d>(fractal: &F, point: Vector3, epsilon: f64) -> Vector3 {
+    let de = |x, y, z| fractal.distance_estimate(point + Vector3::new(x, y, z));
+    Vector3::new(
+        de(epsilon, 0.0, 0.0) - de(-epsilon, 0.0, 0.0),
+        de(0.0, epsilon, 0.0) - de(0.0, -epsilon, 0.0),
+        de(0.0, 0.0, epsilon) - de(0.0, 0.0, -epsilon),
+    )
+    .normalize()
+}
+
+/// Compares the distance along the normal with the distance to the surface,
+/// they are equal when nothing is around, returns `0` for a fully occluded point.
+fn ambient_occlusion<F: Fractal3d>(fractal: &F, point: Vector3, normal: Vector3) -> f64 {
+    let mut occlusion = 0.0;
+    let mut weight = 1.0;
+
+    for i in 1..=5 {
+        let along = OCCLUSION_STEP * i as f64;
+        let distance = fractal.distance_estimate(point + normal * along);
+        occlusion += (along - distance).max(0.0) * weight;
+        weight *= 0.5;
+    }
+
+    (1.0 - occlusion * 8.0).clamp(0.0, 1.0)
+}
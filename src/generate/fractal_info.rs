@@ -1,4 +1,8 @@
+use crate::fractal::{MagnetKind, OrbitTrap};
 use crate::generate::FractalType;
+use crate::ifs::Flame;
+use crate::lsystem::Preset;
+use crate::precision::PrecisePoint;
 use num_complex::Complex64;
 use std::fmt;
 
@@ -6,38 +10,178 @@ use std::fmt;
 pub struct FractalInfo {
     pub fractal_type: FractalType,
     pub domain: Complex64,
-    pub position: [f64; 2],
+    pub power: u32,
+    /// The roots of the polynomial of the Newton fractal.
+    pub roots: Vec<Complex64>,
+    /// The formula given by the user.
+    pub formula: Option<String>,
+    /// The sequence of the Lyapunov fractal.
+    pub sequence: Option<String>,
+    /// The weight of the previous orbit value of the Phoenix fractal, `c` is the domain.
+    pub phoenix: Option<Complex64>,
+    /// The rational map of the Magnet fractal.
+    pub magnet: Option<MagnetKind>,
+    /// The relaxation factor of the Nova fractals.
+    pub relaxation: Option<f64>,
+    /// The genome of the fractal flame.
+    pub flame: Option<Flame>,
+    /// The preset of the L-system.
+    pub lsystem: Option<Preset>,
+    /// The orbit trap used to color the fractal, if any.
+    pub trap: Option<OrbitTrap>,
+    /// The center of the image, precise enough to reproduce deep zooms.
+    pub position: PrecisePoint,
     pub zoom: f64,
+    /// The rotation of the camera in degrees.
+    pub rotation: f64,
 }
 
-impl fmt::Display for FractalInfo {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let [x, y] = self.position;
+impl FractalInfo {
+    /// Same as the `Display` text but shortened to fit in `max_chars` characters,
+    /// the digits of the position are dropped first, down to the three first ones,
+    /// then the end of the text is cut.
+    pub fn message(&self, max_chars: usize) -> String {
+        let mut message = String::new();
+        for digits in (3..=self.position_digits()).rev() {
+            message.clear();
+            self.describe(&mut message, digits).expect("a String can always be written to");
+            if message.chars().count() <= max_chars {
+                return message;
+            }
+        }
+
+        let mut message: String = message.chars().take(max_chars.saturating_sub(1)).collect();
+        message.push('…');
+        message
+    }
+
+    /// The number of fraction digits needed to locate the position at this zoom.
+    fn position_digits(&self) -> usize {
+        self.zoom.recip().log10().ceil().max(0.0) as usize + 3
+    }
+
+    fn describe<W: fmt::Write>(&self, f: &mut W, digits: usize) -> fmt::Result {
+        let Complex64 { re, im } = self.domain;
 
         match self.fractal_type {
             FractalType::Julia => {
-                let Complex64 { re, im } = self.domain;
-                write!(
-                    f,
-                    "Here is the julia fractal in the domain ({:.3}, {:.3}), \
-                       focus is on the area ({:.3}, {:.3}) with the zoom set on {:.3}x.",
-                    re,
-                    im,
-                    x,
-                    y,
-                    self.zoom.recip()
-                )
+                write!(f, "Here is the julia fractal in the domain ({:.3}, {:.3})", re, im)?
+            }
+            FractalType::Mandelbrot => write!(f, "Here is the mandelbrot fractal")?,
+            FractalType::BurningShip => write!(f, "Here is the burning ship fractal")?,
+            FractalType::BurningShipJulia => write!(
+                f,
+                "Here is the burning ship julia fractal in the domain ({:.3}, {:.3})",
+                re, im
+            )?,
+            FractalType::Tricorn => write!(f, "Here is the tricorn fractal")?,
+            FractalType::TricornJulia => {
+                write!(f, "Here is the tricorn julia fractal in the domain ({:.3}, {:.3})", re, im)?
+            }
+            FractalType::Multibrot => {
+                write!(f, "Here is the multibrot fractal of power {}", self.power)?
             }
-            FractalType::Mandelbrot => {
+            FractalType::MultibrotJulia => write!(
+                f,
+                "Here is the multibrot julia fractal of power {} in the domain ({:.3}, {:.3})",
+                self.power, re, im
+            )?,
+            FractalType::Newton => write!(
+                f,
+                "Here is the newton fractal of a polynomial of degree {}",
+                self.roots.len()
+            )?,
+            FractalType::Lyapunov => write!(
+                f,
+                "Here is the lyapunov fractal of the sequence {}",
+                self.sequence.as_ref().map_or("", |s| s.as_str())
+            )?,
+            FractalType::Phoenix => {
+                let p = self.phoenix.unwrap_or_default();
                 write!(
                     f,
-                    "Here is the mandelbrot fractal, \
-                       focus is on the area ({:.3}, {:.3}) with the zoom set on {:.3}x.",
-                    x,
-                    y,
-                    self.zoom.recip()
-                )
+                    "Here is the phoenix fractal in the domain ({:.3}, {:.3}) with p = ({:.3}, {:.3})",
+                    re, im, p.re, p.im
+                )?
             }
+            FractalType::Magnet => match self.magnet {
+                Some(MagnetKind::TypeII) => write!(f, "Here is the magnet fractal of type II")?,
+                _ => write!(f, "Here is the magnet fractal of type I")?,
+            },
+            FractalType::Nova => write!(
+                f,
+                "Here is the nova fractal of power {} with a relaxation of {:.3}",
+                self.power,
+                self.relaxation.unwrap_or(1.0)
+            )?,
+            FractalType::NovaJulia => write!(
+                f,
+                "Here is the nova julia fractal of power {} with a relaxation of {:.3} in the domain ({:.3}, {:.3})",
+                self.power,
+                self.relaxation.unwrap_or(1.0),
+                re,
+                im
+            )?,
+            FractalType::Flame => match &self.flame {
+                Some(flame) => {
+                    let variations: Vec<_> =
+                        flame.variations().iter().map(|v| v.to_string()).collect();
+                    write!(
+                        f,
+                        "Here is a fractal flame of {} transforms using the {} variations",
+                        flame.transforms().len(),
+                        variations.join(", ")
+                    )?
+                }
+                None => write!(f, "Here is a fractal flame")?,
+            },
+            FractalType::LSystem => match self.lsystem {
+                Some(preset) => write!(f, "Here is the {} l-system", preset)?,
+                None => write!(f, "Here is an l-system")?,
+            },
+            FractalType::Formula => write!(
+                f,
+                "Here is the fractal of the formula {}",
+                self.formula.as_ref().map_or("", |f| f.as_str())
+            )?,
+            FractalType::FormulaJulia => write!(
+                f,
+                "Here is the julia fractal of the formula {} in the domain ({:.3}, {:.3})",
+                self.formula.as_ref().map_or("", |f| f.as_str()),
+                re,
+                im
+            )?,
         }
+
+        match self.trap {
+            Some(OrbitTrap::Point(_)) => write!(f, " colored by a point orbit trap")?,
+            Some(OrbitTrap::Line { .. }) => write!(f, " colored by a line orbit trap")?,
+            Some(OrbitTrap::Cross(_)) => write!(f, " colored by a cross orbit trap")?,
+            Some(OrbitTrap::Circle { .. }) => write!(f, " colored by a circle orbit trap")?,
+            None => (),
+        }
+
+        write!(f, ", focus is on the area {:.*}", digits, self.position)?;
+
+        // the magnification of deep zooms is too long to be written in full
+        let magnification = self.zoom.recip();
+        if magnification < 1e6 {
+            write!(f, " with the zoom set on {:.3}x", magnification)?;
+        } else {
+            write!(f, " with the zoom set on {:.3e}x", magnification)?;
+        }
+
+        if self.rotation != 0.0 {
+            write!(f, " and the view rotated by {:.1} degrees", self.rotation)?;
+        }
+
+        write!(f, ".")
+    }
+}
+
+impl fmt::Display for FractalInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // deep zooms need more digits to be located
+        self.describe(f, self.position_digits())
     }
 }
@@ -4,18 +4,30 @@ mod fractal_info;
 pub use self::date_seed::DateSeed;
 pub use self::fractal_info::FractalInfo;
 
+use crate::animation::{CameraPath, JuliaMorph, Video};
 use crate::camera::Camera;
-use crate::fractal::{Fractal, Julia, Mandelbrot};
-use crate::image::{edges, produce_image};
+use crate::fractal::Mandelbrot;
+use crate::fractal::{Bailout, BurningShip, BurningShipJulia, Formula, Julia, Lyapunov};
+use crate::fractal::{Convergence, EscapeTime, Fractal, PerturbedMandelbrot, SmoothIterations};
+use crate::fractal::{InteriorColoring, OrbitTrap, TrapColoring};
+use crate::fractal::{Magnet, Multibrot, MultibrotJulia, Newton, Nova, NovaJulia, Phoenix};
+use crate::fractal::{Tricorn, TricornJulia};
+use crate::ifs::{produce_flame_image, ChaosGame, Flame};
+use crate::image::{edges, pixel_size, produce_distance_image, produce_image};
+use crate::image::{produce_lyapunov_image, produce_newton_image, render_offset_image};
 use crate::image::{Antialiazing, ComplexPalette, ScreenDimensions, SubGradient};
+use crate::lsystem::{produce_lsystem_image, Drawing, Preset, Turtle};
+use crate::precision::PrecisePoint;
 use image::{imageops, Rgb, RgbImage};
 use num_complex::Complex64;
 use palette::rgb::LinSrgb;
-use palette::Gradient;
+use palette::{Gradient, Hsv, Mix, RgbHue};
 use pathfinding::dijkstra::dijkstra;
 use rand::distributions::{IndependentSample, Range};
-use rand::Rng;
-use rand_derive::Rand;
+use rand::{Rand, Rng};
+use std::cmp::Ordering;
+use std::f64::consts::PI;
+use std::iter;
 
 fn find_point<P>(start: (u32, u32), image: &RgbImage, predicate: P) -> Option<(u32, u32)>
 where
@@ -47,10 +59,106 @@ where
     result.map(|(path, _)| *path.last().unwrap())
 }
 
-#[derive(Debug, Copy, Clone, Rand)]
+/// Returns an iteration limit that grows from the base limit with the depth of the zoom,
+/// slow escapers of deep areas would be considered inside the fractal otherwise.
+fn max_iterations_for_zoom(base: u32, zoom: f64) -> u32 {
+    let depth = zoom.recip().log10().max(0.0);
+    (base as f64 * (1.0 + depth)) as u32
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum FractalType {
     Julia,
     Mandelbrot,
+    BurningShip,
+    BurningShipJulia,
+    Tricorn,
+    TricornJulia,
+    Multibrot,
+    MultibrotJulia,
+    Newton,
+    /// A formula given by the user, never picked randomly.
+    Formula,
+    FormulaJulia,
+    Lyapunov,
+    Phoenix,
+    Magnet,
+    Nova,
+    NovaJulia,
+    /// A random fractal flame, rendered by the chaos game instead of a dive.
+    Flame,
+    /// A preset L-system, drawn by a turtle instead of a dive.
+    LSystem,
+}
+
+/// The fractal types that can be picked randomly.
+const RANDOM_TYPES: [FractalType; 16] = [
+    FractalType::Julia,
+    FractalType::Mandelbrot,
+    FractalType::BurningShip,
+    FractalType::BurningShipJulia,
+    FractalType::Tricorn,
+    FractalType::TricornJulia,
+    FractalType::Multibrot,
+    FractalType::MultibrotJulia,
+    FractalType::Newton,
+    FractalType::Lyapunov,
+    FractalType::Phoenix,
+    FractalType::Magnet,
+    FractalType::Nova,
+    FractalType::NovaJulia,
+    FractalType::Flame,
+    FractalType::LSystem,
+];
+
+impl Rand for FractalType {
+    fn rand<R: Rng>(rng: &mut R) -> FractalType {
+        RANDOM_TYPES[rng.gen_range(0, RANDOM_TYPES.len())]
+    }
+}
+
+/// Returns a random sequence of `A` and `B` for the Lyapunov fractal,
+/// both letters are used, the fractal is empty otherwise.
+fn random_sequence<R: Rng>(rng: &mut R) -> String {
+    loop {
+        let len = rng.gen_range(2, 9);
+        let sequence: String = (0..len).map(|_| if rng.gen() { 'B' } else { 'A' }).collect();
+        if sequence.contains('A') && sequence.contains('B') {
+            return sequence;
+        }
+    }
+}
+
+/// Picks the constant of a Julia fractal among the hand-picked presets.
+fn random_julia_domain<R: Rng>(rng: &mut R) -> Complex64 {
+    let sub_gradients = Gradient::new(SubGradient::julia_presets());
+    let sub_gradient = sub_gradients.get(rng.gen());
+    let ComplexPalette(domain) = sub_gradient.gradient().get(rng.gen());
+    domain
+}
+
+/// Finds a domain for a Julia fractal by picking a point that slowly escapes
+/// the corresponding parameter plane fractal, these produce detailed Julia sets
+/// that are neither filled blobs nor sparse dust.
+///
+/// The point is searched in the square of the given half side around the origin.
+fn find_julia_domain<F, R>(rng: &mut R, fractal: &F, radius: f64) -> Complex64
+where
+    F: Fractal,
+    R: Rng,
+{
+    let min_iterations = fractal.max_iterations() / 8;
+    let mut domain = Complex64::new(0.0, 0.0);
+
+    for _ in 0..10_000 {
+        domain = Complex64::new(rng.gen_range(-radius, radius), rng.gen_range(-radius, radius));
+        let escape = fractal.iterations(domain.re, domain.im);
+        if escape.escaped() && escape.iterations >= min_iterations {
+            break;
+        }
+    }
+
+    domain
 }
 
 /// Find a good target point that will not be a black area:
@@ -59,31 +167,192 @@ pub enum FractalType {
 ///   - find the nearest black point
 ///   - create an edge image of the first grayscaled image
 ///   - find the nearest white point on the edged image starting from the previous black point
-fn find_target_point<F, R>(
-    rng: &mut R,
-    fractal: &F,
-    camera: &Camera,
-    dimensions: (u32, u32),
-) -> Option<(u32, u32)>
-where
-    F: Fractal + Sync,
-    R: Rng,
-{
-    let (width, height) = dimensions;
+fn find_target_point<R: Rng>(rng: &mut R, grayscaled: &RgbImage) -> Option<(u32, u32)> {
+    let (width, height) = grayscaled.dimensions();
 
-    let grayscaled = produce_image(fractal, camera, dimensions, None, |i| Rgb { data: [i; 3] });
-    let blurred = imageops::blur(&grayscaled, 10.0);
+    let blurred = imageops::blur(grayscaled, 10.0);
     let black_point = {
         let start = (rng.gen_range(0, width), rng.gen_range(0, height));
         find_point(start, &blurred, |p| p.data[0] <= 128)
     };
 
     black_point.and_then(|black_point| {
-        let edged = edges(&grayscaled);
+        let edged = edges(grayscaled);
         find_point(black_point, &edged, |p| p.data[0] >= 128)
     })
 }
 
+/// Produces an image where the pixels that are less than a pixel away
+/// from the fractal boundary are white, using the distance estimates of the fractal.
+fn boundary_image<F>(fractal: &F, camera: &Camera, dimensions: (u32, u32)) -> RgbImage
+where
+    F: Fractal + ?Sized + Sync,
+{
+    produce_distance_image(fractal, camera, dimensions, None, |distance| match distance {
+        Some(d) if d > 0.0 && d < 1.0 => Rgb { data: [255; 3] },
+        _ => Rgb { data: [0; 3] },
+    })
+}
+
+/// Find a good target point using the distance estimates of the fractal:
+/// the nearest point of the boundary from a random starting point,
+/// more reliable than the blur and edge detection of `find_target_point`.
+fn find_boundary_point<R: Rng>(rng: &mut R, boundary: &RgbImage) -> Option<(u32, u32)> {
+    let (width, height) = boundary.dimensions();
+    let start = (rng.gen_range(0, width), rng.gen_range(0, height));
+    find_point(start, boundary, |p| p.data[0] >= 128)
+}
+
+/// Under this zoom `f64` coordinates are no more precise enough
+/// to distinguish the pixels of the Mandelbrot fractal.
+const PERTURBATION_ZOOM: f64 = 1e-12;
+
+/// The share of the points inside the fractal above which the periodicity checking
+/// speeds the frames up, under it the check only slows the escaping orbits down.
+const PERIODICITY_MIN_INTERIOR: f64 = 0.1;
+
+/// Whether the periodicity checking speeds up the rendering of the view,
+/// the points that never escape are counted on a coarse grid.
+fn periodicity_pays_off<F>(fractal: &F, camera: &Camera) -> bool
+where
+    F: Fractal + ?Sized,
+{
+    const GRID: u32 = 32;
+
+    let [cx, cy] = camera.center_f64();
+    let [width, height] = camera.screen_size;
+    let mut inside = 0;
+    for y in 0..GRID {
+        for x in 0..GRID {
+            let x = (x as f64 + 0.5) * width / GRID as f64;
+            let y = (y as f64 + 0.5) * height / GRID as f64;
+            let [x, y] = camera.screen_to_offset([x, y]);
+            if !fractal.iterations(cx + x, cy + y).escaped() {
+                inside += 1;
+            }
+        }
+    }
+
+    inside as f64 / (GRID * GRID) as f64 >= PERIODICITY_MIN_INTERIOR
+}
+
+/// Produces a grayscale image of the escape times of the fractal.
+fn grayscale_image<F>(
+    fractal: &F,
+    perturbable: bool,
+    camera: &Camera,
+    dimensions: (u32, u32),
+) -> RgbImage
+where
+    F: Fractal + ?Sized + Sync,
+{
+    let painter = |e: EscapeTime| Rgb { data: [e.to_u8(); 3] };
+    escape_time_image(fractal, perturbable, camera, dimensions, painter)
+}
+
+/// Produces an image of the escape times of the fractal, deep zooms into
+/// the Mandelbrot fractal are computed by perturbation around the high precision
+/// center of the camera.
+fn escape_time_image<F, C>(
+    fractal: &F,
+    perturbable: bool,
+    camera: &Camera,
+    dimensions: (u32, u32),
+    painter: C,
+) -> RgbImage
+where
+    F: Fractal + ?Sized + Sync,
+    C: Fn(EscapeTime) -> Rgb<u8> + Sync + Send,
+{
+    if perturbable && camera.zoom < PERTURBATION_ZOOM {
+        let fractal = PerturbedMandelbrot::new(camera.center, fractal.max_iterations());
+        render_offset_image(camera, dimensions, None, |x, y| painter(fractal.iterations(x, y)))
+    } else {
+        produce_image(fractal, camera, dimensions, None, painter)
+    }
+}
+
+/// Returns the range of the values visible on screen, the gradient is stretched over it,
+/// the distances to the orbit traps and the interior values have no fixed range.
+///
+/// The points without value are ignored.
+fn visible_range<V>(camera: &Camera, value: V) -> (f64, f64)
+where
+    V: Fn(f64, f64) -> Option<f64>,
+{
+    let [sx, sy] = camera.screen_size;
+    let samples = 64;
+
+    let mut values = Vec::with_capacity(samples * samples);
+    for i in 0..samples {
+        for j in 0..samples {
+            let pos = [sx * i as f64 / samples as f64, sy * j as f64 / samples as f64];
+            let [x, y] = camera.screen_to_offset(pos);
+            if let Some(value) = value(x, y) {
+                values.push(value);
+            }
+        }
+    }
+
+    // the highest values are ignored, they are a minority
+    // that would squeeze the others at the start of the gradient
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+    match (values.first(), values.get(values.len() * 95 / 100)) {
+        (Some(&low), Some(&high)) if high > low => (low, high),
+        _ => (0.0, 1.0),
+    }
+}
+
+/// Returns the position of the iteration count on the escape gradient, the gradient
+/// is cycled every `cycle` iterations, its black end leads back to its deep blue start.
+///
+/// The colors do not depend on the iteration limit, it grows with the zoom.
+fn escape_gradient_index(iterations: f64, cycle: u32) -> f64 {
+    (iterations / cycle as f64).fract()
+}
+
+/// The gradient of the escaping points, from a deep blue to black through white and orange.
+fn escape_gradient() -> Gradient<LinSrgb> {
+    Gradient::with_domain(vec![
+        (0.0, LinSrgb::new(0.0, 0.027, 0.392)),   // 0,    2.7,  39.2
+        (0.16, LinSrgb::new(0.125, 0.42, 0.796)), // 12.5, 42,   79.6
+        (0.42, LinSrgb::new(0.929, 1.0, 1.0)),    // 92.9, 100,  100
+        (0.6425, LinSrgb::new(1.0, 0.667, 0.0)),  // 100,  66.7, 0
+        (0.8575, LinSrgb::new(0.0, 0.008, 0.0)),  // 0,    0.8,  0
+        (1.0, LinSrgb::new(0.0, 0.0, 0.0)),       // 0,    0,    0
+    ])
+}
+
+/// The frames of a video, each one is rendered when it is iterated.
+pub type Frames = Box<dyn Iterator<Item = RgbImage>>;
+
+/// Renders the frames of a Julia morph colored like the videos of the dives,
+/// the view is wide enough to show the whole Julia sets.
+pub fn julia_morph_frames<'a>(
+    morph: &'a JuliaMorph,
+    video: &Video,
+) -> impl Iterator<Item = RgbImage> + 'a {
+    let gradient = escape_gradient();
+    let painter =
+        move |e: EscapeTime| Rgb { data: gradient.get(e.ratio().sqrt() as f32).into_pixel() };
+
+    let (width, height) = video.dimensions();
+    let mut camera = Camera::new([width as f64, height as f64]);
+    camera.zoom = 1.5;
+
+    morph.render(&camera, video, painter)
+}
+
+/// Returns a gradient of three hues evenly spread around the color wheel.
+fn hue_gradient<R: Rng>(rng: &mut R) -> Gradient<LinSrgb> {
+    let hue: f32 = rng.gen_range(0.0, 360.0);
+    let colors = (0..3).map(|i| {
+        let hue = RgbHue::from(hue + i as f32 * 120.0);
+        LinSrgb::from(Hsv::new(hue, 0.85, 1.0))
+    });
+    Gradient::new(colors.collect::<Vec<_>>())
+}
+
 #[derive(Debug)]
 pub struct Generator<R: Rng> {
     rng: R,
@@ -91,6 +360,11 @@ pub struct Generator<R: Rng> {
     shot_dimensions: ScreenDimensions,
     antialiazing: Antialiazing,
     debug_images: bool,
+    deep_zoom: bool,
+    legacy_bailout: bool,
+    formula: Option<Formula>,
+    formula_julia: bool,
+    video: Option<Video>,
 }
 
 impl<R: Rng> Generator<R> {
@@ -101,6 +375,11 @@ impl<R: Rng> Generator<R> {
             shot_dimensions: ScreenDimensions(800, 600),
             antialiazing: Antialiazing::new(4).unwrap(),
             debug_images: true,
+            deep_zoom: false,
+            legacy_bailout: false,
+            formula: None,
+            formula_julia: false,
+            video: None,
         }
     }
 
@@ -124,95 +403,477 @@ impl<R: Rng> Generator<R> {
         self
     }
 
-    pub fn generate(mut self) -> (FractalInfo, RgbImage) {
+    /// Allows the generator to dive much deeper into the Mandelbrot fractal,
+    /// up to zooms around `1e-50`, these images take much more time to generate.
+    pub fn deep_zoom(&mut self, deep_zoom: bool) -> &mut Self {
+        self.deep_zoom = deep_zoom;
+        self
+    }
+
+    /// Reproduces the images the first versions generated for the same seed: a Julia
+    /// or a Mandelbrot fractal dived into with 255 iterations and the legacy escape tests,
+    /// painted with the raw escape times. The deep zoom is ignored in this mode,
+    /// the whole mode is ignored when a formula is given.
+    pub fn legacy_bailout(&mut self, legacy_bailout: bool) -> &mut Self {
+        self.legacy_bailout = legacy_bailout;
+        self
+    }
+
+    /// Dives into the fractal of this formula instead of a random one.
+    pub fn formula(&mut self, formula: Formula) -> &mut Self {
+        self.formula = Some(formula);
+        self
+    }
+
+    /// Uses the formula in Julia mode, the constant is picked randomly.
+    pub fn formula_julia(&mut self, formula_julia: bool) -> &mut Self {
+        self.formula_julia = formula_julia;
+        self
+    }
+
+    /// Renders a video of the dive in color along with the final image,
+    /// see `generate_with_frames`. The flames and the L-systems are not dived into.
+    pub fn video(&mut self, video: Video) -> &mut Self {
+        self.video = Some(video);
+        self
+    }
+
+    pub fn generate(self) -> (FractalInfo, RgbImage) {
+        let (info, image, _) = self.generate_with_frames();
+        (info, image)
+    }
+
+    /// Same as `generate` but also returns the frames of the video of the dive,
+    /// there is no frame if no video was asked.
+    ///
+    /// The frames are only rendered when they are iterated,
+    /// they can be written one by one without keeping the whole video in memory.
+    pub fn generate_with_frames(mut self) -> (FractalInfo, RgbImage, Frames) {
         let dimensions = self.dive_dimensions.as_tuple();
         let antialiazing: u32 = self.antialiazing.into();
 
         let (width, height) = dimensions;
         let mut camera = Camera::new([width as f64, height as f64]);
 
-        let fractal: Box<dyn Fractal + Sync>;
-        let fractal_type;
-        let domain;
+        let mut fractal: Box<dyn Fractal + Sync>;
+        let fractal_type = match self.formula {
+            Some(_) if self.formula_julia => FractalType::FormulaJulia,
+            Some(_) => FractalType::Formula,
+            None if self.legacy_bailout => return self.generate_legacy(),
+            None => self.rng.gen(),
+        };
+        match fractal_type {
+            FractalType::Flame => {
+                let (info, image) = self.generate_flame();
+                return (info, image, Box::new(iter::empty()));
+            }
+            FractalType::LSystem => {
+                let (info, image) = self.generate_lsystem();
+                return (info, image, Box::new(iter::empty()));
+            }
+            _ => (),
+        }
+
+        let mut domain = Complex64::new(0.0, 0.0);
+        let mut power = 2;
+        let mut newton = None;
+        let mut lyapunov = None;
+        let mut phoenix = None;
+        let mut magnet = None;
+        let mut relaxation = None;
         let zoom_steps;
 
-        match self.rng.gen() {
+        match fractal_type {
             FractalType::Julia => {
-                // https://upload.wikimedia.org/wikipedia/commons/a/a9/Julia-Teppich.png
-                // http://www.karlsims.com/julia.html
-                let sub_gradients = Gradient::new(vec![
-                    SubGradient::new(
-                        ComplexPalette::new(-0.8, 0.3),
-                        ComplexPalette::new(-0.8, 0.15),
-                    ),
-                    SubGradient::new(
-                        ComplexPalette::new(-0.6, 0.7),
-                        ComplexPalette::new(-0.6, 0.5),
-                    ),
-                    SubGradient::new(
-                        ComplexPalette::new(-0.4, 0.65),
-                        ComplexPalette::new(-0.4, 0.6),
-                    ),
-                    SubGradient::new(
-                        ComplexPalette::new(-0.2, 0.9),
-                        ComplexPalette::new(-0.2, 0.8),
-                    ),
-                    SubGradient::new(ComplexPalette::new(0.0, 1.0), ComplexPalette::new(0.0, 0.7)),
-                    SubGradient::new(
-                        ComplexPalette::new(0.19, 0.6),
-                        ComplexPalette::new(0.19, 0.552),
-                    ),
-                    SubGradient::new(
-                        ComplexPalette::new(0.28, 0.01),
-                        ComplexPalette::new(0.28, -0.01),
-                    ),
-                    SubGradient::new(
-                        ComplexPalette::new(0.29, 0.6),
-                        ComplexPalette::new(0.29, 0.55),
-                    ),
-                ]);
-
-                let sub_gradient = sub_gradients.get(self.rng.gen());
-                let gradient = sub_gradient.gradient();
-                let ComplexPalette(Complex64 { re, im }) = gradient.get(self.rng.gen());
-
-                fractal = Box::new(Julia::new(re, im));
-                fractal_type = FractalType::Julia;
-                domain = Complex64::new(re, im);
+                domain = random_julia_domain(&mut self.rng);
+                fractal = Box::new(Julia::new(domain.re, domain.im));
                 zoom_steps = self.rng.gen_range(0, 44);
             }
             FractalType::Mandelbrot => {
                 fractal = Box::new(Mandelbrot::new());
-                fractal_type = FractalType::Mandelbrot;
-                domain = Complex64::new(0.0, 0.0);
+                zoom_steps = self.rng.gen_range(20, 44) * if self.deep_zoom { 6 } else { 1 };
+            }
+            FractalType::BurningShip => {
+                fractal = Box::new(BurningShip::new());
+                zoom_steps = self.rng.gen_range(20, 44);
+            }
+            FractalType::BurningShipJulia => {
+                domain = find_julia_domain(&mut self.rng, &BurningShip::new(), 2.0);
+                fractal = Box::new(BurningShipJulia::new(domain.re, domain.im));
+                zoom_steps = self.rng.gen_range(0, 44);
+            }
+            FractalType::Tricorn => {
+                fractal = Box::new(Tricorn::new());
+                zoom_steps = self.rng.gen_range(20, 44);
+            }
+            FractalType::TricornJulia => {
+                domain = find_julia_domain(&mut self.rng, &Tricorn::new(), 2.0);
+                fractal = Box::new(TricornJulia::new(domain.re, domain.im));
+                zoom_steps = self.rng.gen_range(0, 44);
+            }
+            FractalType::Multibrot => {
+                power = self.rng.gen_range(3, 7);
+                fractal = Box::new(Multibrot::new(power));
                 zoom_steps = self.rng.gen_range(20, 44);
             }
+            FractalType::MultibrotJulia => {
+                power = self.rng.gen_range(3, 7);
+                domain = find_julia_domain(&mut self.rng, &Multibrot::new(power), 2.0);
+                fractal = Box::new(MultibrotJulia::new(domain.re, domain.im, power));
+                zoom_steps = self.rng.gen_range(0, 44);
+            }
+            FractalType::Newton => {
+                // a cubic or a quintic polynomial with roots spread around the origin
+                let degree = if self.rng.gen() { 3 } else { 5 };
+                let roots = (0..degree)
+                    .map(|_| {
+                        let radius = self.rng.gen_range(0.5, 1.5);
+                        let angle = self.rng.gen_range(0.0, 2.0 * PI);
+                        Complex64::from_polar(&radius, &angle)
+                    })
+                    .collect();
+
+                let fractal_newton = Newton::from_roots(roots);
+                fractal = Box::new(fractal_newton.clone());
+                newton = Some(fractal_newton);
+                zoom_steps = self.rng.gen_range(10, 44);
+            }
+            FractalType::Lyapunov => {
+                let fractal_lyapunov = Lyapunov::new(&random_sequence(&mut self.rng)).unwrap();
+                fractal = Box::new(fractal_lyapunov.clone());
+                lyapunov = Some(fractal_lyapunov);
+                zoom_steps = self.rng.gen_range(0, 4);
+
+                // the growth rates of the logistic map are interesting between 2 and 4
+                camera.center = PrecisePoint::from_f64([3.0, 3.0]);
+            }
+            FractalType::Phoenix => {
+                // around the parameters Ushiki used, c = 0.5667 and p = -0.5
+                domain =
+                    Complex64::new(self.rng.gen_range(0.45, 0.6), self.rng.gen_range(-0.1, 0.1));
+                let p =
+                    Complex64::new(self.rng.gen_range(-0.6, -0.4), self.rng.gen_range(-0.1, 0.1));
+                fractal = Box::new(Phoenix::new(domain, p));
+                phoenix = Some(p);
+                zoom_steps = self.rng.gen_range(0, 44);
+            }
+            FractalType::Magnet => {
+                let kind = self.rng.gen();
+                fractal = Box::new(Magnet::new(kind));
+                magnet = Some(kind);
+                zoom_steps = self.rng.gen_range(5, 25);
+
+                // the whole fractal lies around the attractor z = 1
+                camera.center = PrecisePoint::from_f64([1.5, 0.0]);
+            }
+            FractalType::Nova => {
+                power = self.rng.gen_range(3, 6);
+                let factor = self.rng.gen_range(0.5, 1.5);
+                fractal = Box::new(Nova::new(power, factor));
+                relaxation = Some(factor);
+                zoom_steps = self.rng.gen_range(10, 44);
+            }
+            FractalType::NovaJulia => {
+                power = self.rng.gen_range(3, 6);
+                let factor = self.rng.gen_range(0.5, 1.5);
+                domain = find_julia_domain(&mut self.rng, &Nova::new(power, factor), 1.0);
+                fractal = Box::new(NovaJulia::new(domain.re, domain.im, power, factor));
+                relaxation = Some(factor);
+                zoom_steps = self.rng.gen_range(0, 44);
+            }
+            FractalType::Flame | FractalType::LSystem => unreachable!("not dived into"),
+            FractalType::Formula => {
+                fractal = Box::new(self.formula.clone().expect("a formula must be given"));
+                zoom_steps = self.rng.gen_range(20, 44);
+            }
+            FractalType::FormulaJulia => {
+                let formula = self.formula.as_ref().expect("a formula must be given");
+                domain = find_julia_domain(&mut self.rng, formula, 2.0);
+                fractal = Box::new(formula.to_julia(domain.re, domain.im));
+                zoom_steps = self.rng.gen_range(0, 44);
+            }
         };
 
+        // a tilted view gives more variety to the spirals
+        camera.rotation = self.rng.gen_range(0.0, 360.0);
+
         let zoom_distr = Range::new(0.93, 0.97);
-        let gradient = Gradient::with_domain(vec![
-            (0.0, LinSrgb::new(0.0, 0.027, 0.392)),   // 0,    2.7,  39.2
-            (0.16, LinSrgb::new(0.125, 0.42, 0.796)), // 12.5, 42,   79.6
-            (0.42, LinSrgb::new(0.929, 1.0, 1.0)),    // 92.9, 100,  100
-            (0.6425, LinSrgb::new(1.0, 0.667, 0.0)),  // 100,  66.7, 0
-            (0.8575, LinSrgb::new(0.0, 0.008, 0.0)),  // 0,    0.8,  0
-            (1.0, LinSrgb::new(0.0, 0.0, 0.0)),       // 0,    0,    0
+        let gradient = escape_gradient();
+        let interior_gradient = Gradient::new(vec![
+            LinSrgb::new(0.0, 0.0, 0.0),
+            LinSrgb::new(0.122, 0.039, 0.251),
+            LinSrgb::new(0.584, 0.165, 0.329),
+            LinSrgb::new(1.0, 0.667, 0.0),
         ]);
 
-        let painter = |i| {
-            let color = gradient.get(i as f32 / 255.0);
-            Rgb { data: color.into_pixel() }
-        };
+        let base_iterations = fractal.max_iterations();
+        let perturbable = fractal_type == FractalType::Mandelbrot;
+        let estimable =
+            fractal_type == FractalType::Mandelbrot || fractal_type == FractalType::Julia;
 
         // to zoom into the fractal:
         //   - find a good target point using the current camera
         //   - zoom using the camera into the current image
         //   - repeat the first step until the max number of iteration is reached
         //     or a target point can't be found
+        let mut path = CameraPath::new();
+        path.keyframe(0.0, &camera);
+
         for i in 0..zoom_steps {
-            match find_target_point(&mut self.rng, &fractal, &camera, dimensions) {
+            let target = if estimable && !(perturbable && camera.zoom < PERTURBATION_ZOOM) {
+                let boundary = boundary_image(&fractal, &camera, dimensions);
+                find_boundary_point(&mut self.rng, &boundary)
+            } else {
+                fractal.set_periodicity_checking(periodicity_pays_off(&fractal, &camera));
+                let grayscaled = grayscale_image(&fractal, perturbable, &camera, dimensions);
+                find_target_point(&mut self.rng, &grayscaled)
+            };
+
+            match target {
                 Some((x, y)) => {
-                    let [cx, cy] = camera.center;
+                    let start = camera.center;
+                    let [ox, oy] = camera.screen_to_offset([x as f64, y as f64]);
+
+                    for n in 0..10 {
+                        let zoom_multiplier = zoom_distr.ind_sample(&mut self.rng);
+                        let zoom = camera.zoom * zoom_multiplier;
+
+                        let t = n as f64 / 10.0;
+                        let target = start.offset([t * ox, t * oy]);
+
+                        camera.target_on_precise(&target, zoom);
+                        fractal.set_max_iterations(max_iterations_for_zoom(
+                            base_iterations,
+                            camera.zoom,
+                        ));
+
+                        if self.debug_images {
+                            let image = grayscale_image(&fractal, perturbable, &camera, dimensions);
+                            edges(&image)
+                                .save(format!("./spotted-area-{:03}-{:03}.png", i, n))
+                                .unwrap();
+                        }
+                    }
+
+                    path.keyframe((i + 1) as f64, &camera);
+                }
+                None => break,
+            }
+        }
+
+        let deep_shot = perturbable && camera.zoom < PERTURBATION_ZOOM;
+        fractal.set_periodicity_checking(periodicity_pays_off(&fractal, &camera));
+
+        // the orbit traps give more variety to the fractals that support them
+        let trap = if estimable && !deep_shot {
+            if self.rng.gen_weighted_bool(3) {
+                Some((self.rng.gen::<OrbitTrap>(), self.rng.gen::<TrapColoring>()))
+            } else {
+                None
+            }
+        } else {
+            None
+        };
+
+        // the interior of the Julia fractals is shaded by the values their orbits end on,
+        // the Mandelbrot fractal can also show the periods of the cycles or the interior distance
+        let interior = if newton.is_some() || lyapunov.is_some() || trap.is_some() || deep_shot {
+            None
+        } else if fractal_type == FractalType::Mandelbrot {
+            Some(self.rng.gen::<InteriorColoring>())
+        } else {
+            Some(InteriorColoring::FinalMagnitude)
+        };
+
+        let dimensions = self.shot_dimensions.as_tuple();
+        let image = match (newton.as_mut(), lyapunov.as_mut(), trap) {
+            (Some(newton), _, _) => {
+                // every root basin gets its own hue, points that converge slowly are darker
+                let roots = newton.roots().len();
+                let basin_painter = |c: Convergence| match c.root {
+                    Some(root) => {
+                        let hue = RgbHue::from(root as f32 * 360.0 / roots as f32);
+                        let value = (-c.smooth_iterations / 16.0).exp() as f32;
+                        let color = LinSrgb::from(Hsv::new(hue, 0.8, 0.2 + 0.8 * value));
+                        Rgb { data: color.into_pixel() }
+                    }
+                    None => Rgb { data: [0; 3] },
+                };
+
+                newton.set_max_iterations(fractal.max_iterations());
+                produce_newton_image(newton, &camera, dimensions, Some(antialiazing), basin_painter)
+            }
+            (None, Some(lyapunov), _) => {
+                // the stable points go from white to black through orange, the more
+                // stable the darker, the chaotic ones go from white to dark blue
+                let painter = |exponent: f64| {
+                    let i = if exponent < 0.0 {
+                        0.42 + (0.8575 - 0.42) * (1.0 - exponent.exp())
+                    } else if exponent >= 0.0 {
+                        0.42 * (-4.0 * exponent).exp()
+                    } else {
+                        0.0
+                    };
+                    Rgb { data: gradient.get(i as f32).into_pixel() }
+                };
+
+                lyapunov.set_max_iterations(fractal.max_iterations());
+                produce_lyapunov_image(lyapunov, &camera, dimensions, Some(antialiazing), painter)
+            }
+            (None, None, Some((trap, coloring))) => {
+                let [cx, cy] = camera.center_f64();
+
+                let value = |x: f64, y: f64| {
+                    fractal.orbit_trap(cx + x, cy + y, &trap).map(|r| coloring.value(&r))
+                };
+
+                // the orbits that come close to the trap are painted with
+                // the start of the gradient, the farthest ones fade into its end
+                let (low, high) = visible_range(&camera, value);
+                render_offset_image(&camera, dimensions, Some(antialiazing), |x, y| {
+                    let i = value(x, y).map_or(1.0, |v| (v - low) / (high - low));
+                    let color = gradient.get(i.clamp(0.0, 1.0) as f32);
+                    Rgb { data: color.into_pixel() }
+                })
+            }
+            (None, None, None) => {
+                let [cx, cy] = camera.center_f64();
+                let perturbed = if deep_shot {
+                    Some(PerturbedMandelbrot::new(camera.center, fractal.max_iterations()))
+                } else {
+                    None
+                };
+
+                let smooth = |x: f64, y: f64| match perturbed {
+                    Some(ref perturbed) => perturbed.smooth_iterations(x, y),
+                    None => fractal.smooth_iterations(cx + x, cy + y),
+                };
+
+                let inside = |x: f64, y: f64| {
+                    let coloring = interior?;
+                    coloring.value(&fractal.orbit(cx + x, cy + y)?)
+                };
+
+                // points that never escape are painted with the end of the gradient,
+                // or with the interior gradient when their orbits tell more about them
+                let (inside_low, inside_high) = visible_range(&camera, inside);
+                let max_iterations = fractal.max_iterations() as f64;
+                let painter = |x: f64, y: f64, s: SmoothIterations| {
+                    if s.escaped {
+                        let iterations = s.value * max_iterations;
+                        return gradient
+                            .get(escape_gradient_index(iterations, base_iterations) as f32);
+                    }
+                    match inside(x, y) {
+                        Some(value) => {
+                            let i = (value - inside_low) / (inside_high - inside_low);
+                            interior_gradient.get(i.clamp(0.0, 1.0) as f32)
+                        }
+                        None => gradient.get(1.0),
+                    }
+                };
+
+                // the filaments that are thinner than a pixel are outlined
+                // by darkening the points that are less than a pixel away from the boundary
+                let outlined = estimable && perturbed.is_none();
+                let pixel_size = pixel_size(&camera, dimensions, Some(antialiazing));
+                let black = LinSrgb::new(0.0, 0.0, 0.0);
+
+                render_offset_image(&camera, dimensions, Some(antialiazing), |x, y| {
+                    let escape = smooth(x, y);
+                    let mut color = painter(x, y, escape);
+                    if outlined && escape.escaped {
+                        if let Some(distance) = fractal.distance_estimate(cx + x, cy + y) {
+                            let shade = (distance / pixel_size).min(1.0) as f32;
+                            color = color.mix(&black, 1.0 - shade);
+                        }
+                    }
+                    Rgb { data: color.into_pixel() }
+                })
+            }
+        };
+
+        // the frames are colored with the gradient of the shot, most of the points escape
+        // early in a cycle so the square root spreads them, the iteration limit follows the zoom
+        let frames: Frames = match self.video {
+            Some(video) => {
+                let cameras: Vec<_> = video.cameras(&path).collect();
+                Box::new(cameras.into_iter().map(move |camera| {
+                    let painter = |e: EscapeTime| {
+                        let i = if e.escaped() {
+                            escape_gradient_index(e.iterations as f64, base_iterations).sqrt()
+                        } else {
+                            1.0
+                        };
+                        Rgb { data: gradient.get(i as f32).into_pixel() }
+                    };
+                    let max_iterations = max_iterations_for_zoom(base_iterations, camera.zoom);
+                    fractal.set_max_iterations(max_iterations);
+                    fractal.set_periodicity_checking(periodicity_pays_off(&fractal, &camera));
+                    let dimensions = video.dimensions();
+                    escape_time_image(&fractal, perturbable, &camera, dimensions, painter)
+                }))
+            }
+            None => Box::new(iter::empty()),
+        };
+
+        let info = FractalInfo {
+            fractal_type,
+            domain,
+            power,
+            roots: newton.map(|n| n.roots().to_vec()).unwrap_or_default(),
+            formula: self.formula.map(|f| f.source().to_string()),
+            sequence: lyapunov.map(|l| l.sequence()),
+            phoenix,
+            magnet,
+            relaxation,
+            flame: None,
+            lsystem: None,
+            trap: trap.map(|(trap, _)| trap),
+            position: camera.center,
+            zoom: camera.zoom,
+            rotation: camera.rotation,
+        };
+
+        (info, image, frames)
+    }
+
+    /// The dive of the first versions, see `legacy_bailout`, the random draws are the same
+    /// and nothing that was added since changes the images.
+    fn generate_legacy(mut self) -> (FractalInfo, RgbImage, Frames) {
+        let dimensions = self.dive_dimensions.as_tuple();
+        let antialiazing: u32 = self.antialiazing.into();
+
+        let (width, height) = dimensions;
+        let mut camera = Camera::new([width as f64, height as f64]);
+
+        // the first versions only knew these two types, a boolean picked one of them
+        let mut domain = Complex64::new(0.0, 0.0);
+        let (fractal_type, mut fractal, zoom_steps): (_, Box<dyn Fractal + Sync>, _) =
+            if self.rng.gen() {
+                domain = random_julia_domain(&mut self.rng);
+                let fractal = Box::new(Julia::new(domain.re, domain.im));
+                (FractalType::Julia, fractal, self.rng.gen_range(0, 44))
+            } else {
+                (FractalType::Mandelbrot, Box::new(Mandelbrot::new()), self.rng.gen_range(20, 44))
+            };
+
+        // the iteration limit stays at the default of 255, the escape times fitted in a byte
+        fractal.set_bailout(Bailout::Legacy);
+
+        let zoom_distr = Range::new(0.93, 0.97);
+        let gradient = escape_gradient();
+        let painter =
+            |e: EscapeTime| Rgb { data: gradient.get(e.to_u8() as f32 / 255.0).into_pixel() };
+
+        let mut path = CameraPath::new();
+        path.keyframe(0.0, &camera);
+
+        for i in 0..zoom_steps {
+            let grayscaled = grayscale_image(&fractal, false, &camera, dimensions);
+            match find_target_point(&mut self.rng, &grayscaled) {
+                Some((x, y)) => {
+                    let [cx, cy] = camera.center_f64();
                     let [x, y] = camera.screen_to_world([x as f64, y as f64]);
 
                     for n in 0..10 {
@@ -223,17 +884,22 @@ impl<R: Rng> Generator<R> {
                         let x = cx + t * (x - cx);
                         let y = cy + t * (y - cy);
 
-                        camera.target_on_world([x, y], zoom);
+                        // the center is moved in `f64` like it was,
+                        // `target_on_world` does not round the same way
+                        let [ccx, ccy] = camera.center_f64();
+                        camera.center =
+                            PrecisePoint::from_f64([(ccx - x) * zoom + x, (ccy - y) * zoom + y]);
+                        camera.zoom = zoom;
 
                         if self.debug_images {
-                            let image = produce_image(&fractal, &camera, dimensions, None, |i| {
-                                Rgb { data: [i; 3] }
-                            });
+                            let image = grayscale_image(&fractal, false, &camera, dimensions);
                             edges(&image)
                                 .save(format!("./spotted-area-{:03}-{:03}.png", i, n))
                                 .unwrap();
                         }
                     }
+
+                    path.keyframe((i + 1) as f64, &camera);
                 }
                 None => break,
             }
@@ -241,7 +907,110 @@ impl<R: Rng> Generator<R> {
 
         let dimensions = self.shot_dimensions.as_tuple();
         let image = produce_image(&fractal, &camera, dimensions, Some(antialiazing), painter);
-        let info = FractalInfo { fractal_type, domain, position: camera.center, zoom: camera.zoom };
+
+        let frames: Frames = match self.video {
+            Some(video) => {
+                let cameras: Vec<_> = video.cameras(&path).collect();
+                Box::new(cameras.into_iter().map(move |camera| {
+                    let painter = |e: EscapeTime| Rgb {
+                        data: gradient.get(e.to_u8() as f32 / 255.0).into_pixel(),
+                    };
+                    produce_image(&fractal, &camera, video.dimensions(), None, painter)
+                }))
+            }
+            None => Box::new(iter::empty()),
+        };
+
+        let info = FractalInfo {
+            fractal_type,
+            domain,
+            power: 2,
+            roots: Vec::new(),
+            formula: None,
+            sequence: None,
+            phoenix: None,
+            magnet: None,
+            relaxation: None,
+            flame: None,
+            lsystem: None,
+            trap: None,
+            position: camera.center,
+            zoom: camera.zoom,
+            rotation: camera.rotation,
+        };
+
+        (info, image, frames)
+    }
+
+    /// Renders a random flame genome, the flames are not dived into,
+    /// the camera frames their whole attractor.
+    fn generate_flame(mut self) -> (FractalInfo, RgbImage) {
+        let (width, height) = self.shot_dimensions.as_tuple();
+        let mut camera = Camera::new([width as f64, height as f64]);
+
+        let flame: Flame = self.rng.gen();
+        flame.frame(&mut camera);
+
+        let gradient = hue_gradient(&mut self.rng);
+
+        let mut game = ChaosGame::new();
+        game.samples(width * height * 20).seed(self.rng.gen());
+        let image = produce_flame_image(&game, &flame, &camera, (width, height), &gradient);
+
+        let info = FractalInfo {
+            fractal_type: FractalType::Flame,
+            domain: Complex64::new(0.0, 0.0),
+            power: 2,
+            roots: Vec::new(),
+            formula: None,
+            sequence: None,
+            phoenix: None,
+            magnet: None,
+            relaxation: None,
+            flame: Some(flame),
+            lsystem: None,
+            trap: None,
+            position: camera.center,
+            zoom: camera.zoom,
+            rotation: camera.rotation,
+        };
+
+        (info, image)
+    }
+
+    /// Draws a preset L-system, the camera frames the whole drawing.
+    fn generate_lsystem(mut self) -> (FractalInfo, RgbImage) {
+        let (width, height) = self.shot_dimensions.as_tuple();
+        let mut camera = Camera::new([width as f64, height as f64]);
+
+        let preset: Preset = self.rng.gen();
+        let lsystem = preset.lsystem();
+        let commands = lsystem.expand(preset.depth());
+        let drawing = Drawing::new(Turtle::for_lsystem(&lsystem, 1.0).draw(&commands));
+        drawing.frame(&mut camera);
+
+        let gradient = hue_gradient(&mut self.rng);
+        let background = LinSrgb::new(0.0, 0.0, 0.0);
+        let image =
+            produce_lsystem_image(&drawing, &camera, (width, height), &gradient, background);
+
+        let info = FractalInfo {
+            fractal_type: FractalType::LSystem,
+            domain: Complex64::new(0.0, 0.0),
+            power: 2,
+            roots: Vec::new(),
+            formula: None,
+            sequence: None,
+            phoenix: None,
+            magnet: None,
+            relaxation: None,
+            flame: None,
+            lsystem: Some(preset),
+            trap: None,
+            position: camera.center,
+            zoom: camera.zoom,
+            rotation: camera.rotation,
+        };
 
         (info, image)
     }
@@ -0,0 +1,44 @@
+use rand::{Rand, Rng};
+
+/// An affine map of the plane, `(x, y)` goes to `(a·x + b·y + c, d·x + e·y + f)`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Affine {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+    pub d: f64,
+    pub e: f64,
+    pub f: f64,
+}
+
+impl Affine {
+    pub fn new(a: f64, b: f64, c: f64, d: f64, e: f64, f: f64) -> Affine {
+        Affine { a, b, c, d, e, f }
+    }
+
+    /// Scales the plane by the factor then moves it.
+    pub fn scale(factor: f64, translation: [f64; 2]) -> Affine {
+        let [tx, ty] = translation;
+        Affine::new(factor, 0.0, tx, 0.0, factor, ty)
+    }
+
+    pub fn apply(&self, point: [f64; 2]) -> [f64; 2] {
+        let [x, y] = point;
+        [self.a * x + self.b * y + self.c, self.d * x + self.e * y + self.f]
+    }
+}
+
+impl Rand for Affine {
+    /// The linear part is contracting, the attractor stays in a bounded area.
+    fn rand<R: Rng>(rng: &mut R) -> Affine {
+        let mut coefficient = || rng.gen_range(-1.0f64, 1.0);
+        loop {
+            let (a, b, c) = (coefficient(), coefficient(), coefficient());
+            let (d, e, f) = (coefficient(), coefficient(), coefficient());
+
+            if a * a + d * d < 1.0 && b * b + e * e < 1.0 && (a * e - b * d).abs() < 1.0 {
+                return Affine::new(a, b, c, d, e, f);
+            }
+        }
+    }
+}
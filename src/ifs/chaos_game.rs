@@ -0,0 +1,159 @@
+use image::RgbImage;
+use palette::rgb::LinSrgb;
+use palette::{Gradient, Mix};
+use rand::{Rng, SeedableRng, XorShiftRng};
+use rayon::prelude::*;
+
+use crate::camera::Camera;
+use crate::ifs::flame::WARMUP_ITERATIONS;
+use crate::ifs::Flame;
+
+/// The number of independent orbits, each one is iterated by a rayon task
+/// with its own random generator.
+const BATCHES: u32 = 64;
+
+/// The densities above this quantile are all considered the densest.
+const DENSITY_QUANTILE: f64 = 0.999;
+
+/// The points farther than this from the origin are considered lost,
+/// the orbit starts again from a random point.
+const MAX_RADIUS: f64 = 1e10;
+
+/// Draws the attractor of a flame by iterating a point through randomly picked
+/// transforms and counting how many times the orbit goes through every pixel.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ChaosGame {
+    samples: u32,
+    seed: u32,
+    gamma: f64,
+}
+
+impl ChaosGame {
+    pub fn new() -> ChaosGame {
+        ChaosGame { samples: 5_000_000, seed: 0, gamma: 2.2 }
+    }
+
+    /// The number of points of the orbits that are plotted.
+    pub fn samples(&mut self, samples: u32) -> &mut Self {
+        self.samples = samples;
+        self
+    }
+
+    /// The seed of the orbits, the same seed produces the same image.
+    pub fn seed(&mut self, seed: u32) -> &mut Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Brightens the less visited pixels, the fine details of the flames are there.
+    pub fn gamma(&mut self, gamma: f64) -> &mut Self {
+        assert!(gamma > 0.0, "gamma must be positive");
+        self.gamma = gamma;
+        self
+    }
+
+    /// Returns the number of points plotted in every pixel, row by row,
+    /// along with the sum of their color indices.
+    pub fn density(&self, flame: &Flame, camera: &Camera, dimensions: (u32, u32)) -> Vec<[f64; 2]> {
+        let (width, height) = dimensions;
+        let len = (width * height) as usize;
+
+        let camera = Camera { screen_size: [width as f64, height as f64], ..*camera };
+        let to_screen = |point: [f64; 2]| -> Option<usize> {
+            let [x, y] = camera.world_to_screen(point)?;
+            Some(y as usize * width as usize + x as usize)
+        };
+
+        (0..BATCHES)
+            .into_par_iter()
+            .map(|batch| {
+                let seed = [self.seed, batch, 0x2545_f491, 0x6c07_8965];
+                let mut rng = XorShiftRng::from_seed(seed);
+                let mut density = vec![[0.0; 2]; len];
+
+                let mut point = [rng.gen_range(-1.0, 1.0), rng.gen_range(-1.0, 1.0)];
+                let mut color: f64 = rng.gen();
+                let mut warmup = WARMUP_ITERATIONS;
+
+                let samples = self.samples / BATCHES + (batch < self.samples % BATCHES) as u32;
+                let mut plotted = 0;
+                while plotted < samples {
+                    let transform = flame.pick(rng.gen());
+                    point = transform.apply(point);
+                    // the color moves halfway toward the one of the transform
+                    color = (color + transform.color) / 2.0;
+
+                    let [x, y] = point;
+                    let r2 = x * x + y * y;
+                    if r2.is_nan() || r2 > MAX_RADIUS * MAX_RADIUS {
+                        point = [rng.gen_range(-1.0, 1.0), rng.gen_range(-1.0, 1.0)];
+                        warmup = WARMUP_ITERATIONS;
+                        continue;
+                    }
+
+                    if warmup > 0 {
+                        warmup -= 1;
+                        continue;
+                    }
+
+                    if let Some(index) = to_screen(point) {
+                        density[index][0] += 1.0;
+                        density[index][1] += color;
+                    }
+                    plotted += 1;
+                }
+
+                density
+            })
+            .reduce(
+                || vec![[0.0; 2]; len],
+                |mut a, b| {
+                    a.iter_mut().zip(b).for_each(|(a, b)| {
+                        a[0] += b[0];
+                        a[1] += b[1];
+                    });
+                    a
+                },
+            )
+    }
+}
+
+impl Default for ChaosGame {
+    fn default() -> ChaosGame {
+        ChaosGame::new()
+    }
+}
+
+/// Produces an image of the flame, the pixels take the average color of the points
+/// plotted in them and their brightness is the logarithm of their density.
+pub fn produce_flame_image(
+    game: &ChaosGame,
+    flame: &Flame,
+    camera: &Camera,
+    dimensions: (u32, u32),
+    gradient: &Gradient<LinSrgb>,
+) -> RgbImage {
+    let (width, height) = dimensions;
+    let density = game.density(flame, camera, dimensions);
+
+    let mut sorted: Vec<_> = density.iter().map(|d| d[0]).filter(|&d| d > 0.0).collect();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let max = match sorted.get((sorted.len() as f64 * DENSITY_QUANTILE) as usize) {
+        Some(&max) => max,
+        None => sorted.last().cloned().unwrap_or(1.0),
+    };
+
+    let black = LinSrgb::new(0.0, 0.0, 0.0);
+    let mut image = RgbImage::new(width, height);
+    image.par_chunks_mut(3).zip(density.par_iter()).for_each(|(p, &[hits, color])| {
+        let data: [u8; 3] = if hits > 0.0 {
+            let alpha = ((1.0 + hits).ln() / (1.0 + max).ln()).min(1.0).powf(game.gamma.recip());
+            black.mix(&gradient.get((color / hits) as f32), alpha as f32).into_pixel()
+        } else {
+            black.into_pixel()
+        };
+        p.copy_from_slice(&data);
+    });
+
+    image
+}
@@ -0,0 +1,148 @@
+use rand::{Rand, Rng, SeedableRng, XorShiftRng};
+
+use crate::camera::Camera;
+use crate::ifs::{Affine, Transform, Variation};
+use crate::precision::PrecisePoint;
+
+/// The number of points of the orbit that are not plotted,
+/// the orbit needs a few iterations to reach the attractor.
+pub(crate) const WARMUP_ITERATIONS: u32 = 20;
+
+/// The number of points used to find the area covered by the attractor.
+const BOUNDS_SAMPLES: usize = 20_000;
+
+/// An iterated function system, the genome of a fractal flame,
+/// its attractor is drawn by the chaos game.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Flame {
+    transforms: Vec<Transform>,
+    total_weight: f64,
+}
+
+impl Flame {
+    pub fn new(transforms: Vec<Transform>) -> Flame {
+        assert!(!transforms.is_empty(), "flame must have at least one transform");
+        let total_weight = transforms.iter().map(|t| t.weight).sum();
+        Flame { transforms, total_weight }
+    }
+
+    /// The Barnsley fern, the y axis of the screen goes down
+    /// so the maps are flipped for the fern to stand upright.
+    pub fn barnsley_fern() -> Flame {
+        Flame::new(vec![
+            Transform::new(Affine::new(0.0, 0.0, 0.0, 0.0, 0.16, 0.0), 0.01, 0.0),
+            Transform::new(Affine::new(0.85, -0.04, 0.0, 0.04, 0.85, -1.6), 0.85, 0.33),
+            Transform::new(Affine::new(0.2, 0.26, 0.0, -0.23, 0.22, -1.6), 0.07, 0.67),
+            Transform::new(Affine::new(-0.15, -0.28, 0.0, -0.26, 0.24, -0.44), 0.07, 1.0),
+        ])
+    }
+
+    /// The Sierpinski carpet, the unit square is divided in nine
+    /// and every part but the center one is mapped to the whole square.
+    pub fn sierpinski_carpet() -> Flame {
+        let transforms = (0..9)
+            .filter(|&i| i != 4)
+            .enumerate()
+            .map(|(n, i)| {
+                let translation = [(i % 3) as f64 / 3.0, (i / 3) as f64 / 3.0];
+                Transform::new(Affine::scale(1.0 / 3.0, translation), 1.0, n as f64 / 7.0)
+            })
+            .collect();
+        Flame::new(transforms)
+    }
+
+    pub fn transforms(&self) -> &[Transform] {
+        &self.transforms
+    }
+
+    /// Returns the different variations used by the transforms.
+    pub fn variations(&self) -> Vec<Variation> {
+        let mut variations = Vec::new();
+        for &(variation, _) in self.transforms.iter().flat_map(|t| &t.variations) {
+            if !variations.contains(&variation) {
+                variations.push(variation);
+            }
+        }
+        variations
+    }
+
+    /// Picks a transform with a probability proportional to its weight,
+    /// `choice` is a random value in `[0, 1[`.
+    pub(crate) fn pick(&self, choice: f64) -> &Transform {
+        let mut remaining = choice * self.total_weight;
+        for transform in &self.transforms {
+            if remaining < transform.weight {
+                return transform;
+            }
+            remaining -= transform.weight;
+        }
+        &self.transforms[self.transforms.len() - 1]
+    }
+
+    /// Moves and zooms the camera to frame the attractor,
+    /// the points that wander far away from it are ignored.
+    pub fn frame(&self, camera: &mut Camera) {
+        let mut rng = XorShiftRng::from_seed([0x2545_f491, 0x9e37_79b9, 0x7f4a_7c15, 0x6c07_8965]);
+
+        let mut xs = Vec::with_capacity(BOUNDS_SAMPLES);
+        let mut ys = Vec::with_capacity(BOUNDS_SAMPLES);
+        let mut point = [rng.gen_range(-1.0, 1.0), rng.gen_range(-1.0, 1.0)];
+
+        for i in 0..BOUNDS_SAMPLES as u32 + WARMUP_ITERATIONS {
+            point = self.pick(rng.gen()).apply(point);
+            if !point[0].is_finite() || !point[1].is_finite() {
+                point = [rng.gen_range(-1.0, 1.0), rng.gen_range(-1.0, 1.0)];
+                continue;
+            }
+            if i >= WARMUP_ITERATIONS {
+                xs.push(point[0]);
+                ys.push(point[1]);
+            }
+        }
+
+        if xs.is_empty() {
+            return;
+        }
+
+        let range = |values: &mut Vec<f64>| {
+            values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            let low = values[values.len() / 200];
+            let high = values[values.len() * 199 / 200];
+            (low, high)
+        };
+        let ((left, right), (top, bottom)) = (range(&mut xs), range(&mut ys));
+
+        // the zoom is the half height of the view, a margin is kept around the attractor
+        let [sx, sy] = camera.screen_size;
+        let half_height = ((bottom - top) / 2.0).max((right - left) / 2.0 * sy / sx);
+
+        camera.center = PrecisePoint::from_f64([(left + right) / 2.0, (top + bottom) / 2.0]);
+        camera.zoom = (half_height * 1.1).max(1e-6);
+    }
+}
+
+impl Rand for Flame {
+    /// A flame of two to five transforms, each one uses up to three variations.
+    fn rand<R: Rng>(rng: &mut R) -> Flame {
+        let count = rng.gen_range(2, 6);
+        let transforms = (0..count)
+            .map(|i| {
+                let affine = rng.gen();
+                let weight = rng.gen_range(0.1, 1.0);
+                let color = i as f64 / (count - 1) as f64;
+
+                let mut variations: Vec<(Variation, f64)> = (0..rng.gen_range(1, 4))
+                    .map(|_| (rng.gen(), rng.gen_range(0.1, 1.0)))
+                    .collect();
+
+                // the weights of the variations sum to one, the points keep their scale
+                let sum: f64 = variations.iter().map(|&(_, w)| w).sum();
+                variations.iter_mut().for_each(|v| v.1 /= sum);
+
+                Transform::with_variations(affine, weight, color, variations)
+            })
+            .collect();
+
+        Flame::new(transforms)
+    }
+}
@@ -0,0 +1,11 @@
+mod affine;
+mod chaos_game;
+mod flame;
+mod transform;
+mod variation;
+
+pub use self::affine::Affine;
+pub use self::chaos_game::{produce_flame_image, ChaosGame};
+pub use self::flame::Flame;
+pub use self::transform::Transform;
+pub use self::variation::Variation;
@@ -0,0 +1,39 @@
+use crate::ifs::{Affine, Variation};
+
+/// One of the functions of an IFS, an affine map followed by a weighted sum of variations.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transform {
+    pub affine: Affine,
+    /// The probability of the transform relatively to the other ones.
+    pub weight: f64,
+    /// The index of the transform in the color gradient, in `[0, 1]`.
+    pub color: f64,
+    pub variations: Vec<(Variation, f64)>,
+}
+
+impl Transform {
+    /// Creates a transform that only applies the affine map, like the classic IFS do.
+    pub fn new(affine: Affine, weight: f64, color: f64) -> Transform {
+        Transform::with_variations(affine, weight, color, vec![(Variation::Linear, 1.0)])
+    }
+
+    pub fn with_variations(
+        affine: Affine,
+        weight: f64,
+        color: f64,
+        variations: Vec<(Variation, f64)>,
+    ) -> Transform {
+        assert!(weight > 0.0, "transform weight must be positive");
+        assert!((0.0..=1.0).contains(&color), "transform color must be in [0, 1]");
+        assert!(!variations.is_empty(), "transform must have at least one variation");
+        Transform { affine, weight, color, variations }
+    }
+
+    pub fn apply(&self, point: [f64; 2]) -> [f64; 2] {
+        let point = self.affine.apply(point);
+        self.variations.iter().fold([0.0, 0.0], |[x, y], &(variation, weight)| {
+            let [vx, vy] = variation.apply(point);
+            [x + weight * vx, y + weight * vy]
+        })
+    }
+}
@@ -0,0 +1,84 @@
+use std::f64::consts::PI;
+use std::fmt;
+
+use rand_derive::Rand;
+
+/// Avoids the divisions by zero of the variations that use the inverse of the radius.
+const EPSILON: f64 = 1e-10;
+
+/// The nonlinear functions the fractal flames apply after the affine maps,
+/// as described in the paper of Scott Draves and Erik Reckase.
+///
+/// http://flam3.com/flame_draves.pdf
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Rand)]
+pub enum Variation {
+    Linear,
+    Sinusoidal,
+    Spherical,
+    Swirl,
+    Horseshoe,
+    Polar,
+    Handkerchief,
+    Heart,
+    Disc,
+    Spiral,
+    Hyperbolic,
+    Diamond,
+}
+
+impl Variation {
+    pub fn apply(&self, point: [f64; 2]) -> [f64; 2] {
+        let [x, y] = point;
+        let r2 = x * x + y * y;
+        let r = r2.sqrt();
+        // the paper measures the angle from the y axis
+        let theta = x.atan2(y);
+
+        match *self {
+            Variation::Linear => [x, y],
+            Variation::Sinusoidal => [x.sin(), y.sin()],
+            Variation::Spherical => [x / (r2 + EPSILON), y / (r2 + EPSILON)],
+            Variation::Swirl => {
+                let (sin, cos) = r2.sin_cos();
+                [x * sin - y * cos, x * cos + y * sin]
+            }
+            Variation::Horseshoe => {
+                let r = r + EPSILON;
+                [(x - y) * (x + y) / r, 2.0 * x * y / r]
+            }
+            Variation::Polar => [theta / PI, r - 1.0],
+            Variation::Handkerchief => [r * (theta + r).sin(), r * (theta - r).cos()],
+            Variation::Heart => [r * (theta * r).sin(), -r * (theta * r).cos()],
+            Variation::Disc => {
+                let (sin, cos) = (PI * r).sin_cos();
+                [theta / PI * sin, theta / PI * cos]
+            }
+            Variation::Spiral => {
+                let r = r + EPSILON;
+                [(theta.cos() + r.sin()) / r, (theta.sin() - r.cos()) / r]
+            }
+            Variation::Hyperbolic => [theta.sin() / (r + EPSILON), r * theta.cos()],
+            Variation::Diamond => [theta.sin() * r.cos(), theta.cos() * r.sin()],
+        }
+    }
+}
+
+impl fmt::Display for Variation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match *self {
+            Variation::Linear => "linear",
+            Variation::Sinusoidal => "sinusoidal",
+            Variation::Spherical => "spherical",
+            Variation::Swirl => "swirl",
+            Variation::Horseshoe => "horseshoe",
+            Variation::Polar => "polar",
+            Variation::Handkerchief => "handkerchief",
+            Variation::Heart => "heart",
+            Variation::Disc => "disc",
+            Variation::Spiral => "spiral",
+            Variation::Hyperbolic => "hyperbolic",
+            Variation::Diamond => "diamond",
+        };
+        f.write_str(name)
+    }
+}
@@ -0,0 +1,184 @@
+use image::RgbImage;
+use num_complex::Complex64;
+use palette::rgb::LinSrgb;
+use palette::Gradient;
+use rand::{Rng, SeedableRng, XorShiftRng};
+use rayon::prelude::*;
+
+use crate::camera::Camera;
+use crate::fractal::{in_main_cardioid, in_period2_bulb};
+
+/// The number of independent batches the samples are split into,
+/// each one is traced by a rayon task with its own random generator.
+const BATCHES: u32 = 64;
+
+/// The densities above this quantile are clipped, a few pixels
+/// are visited far more often than the others and would darken the whole image.
+const DENSITY_QUANTILE: f64 = 0.999;
+
+/// Renders the density of the orbits of the Mandelbrot fractal, the Buddhabrot,
+/// random `c` values are sampled and every value their orbits go through is counted.
+///
+/// The Buddhabrot counts the orbits that escape,
+/// the Anti-Buddhabrot counts the ones that never escape.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Buddhabrot {
+    samples: u32,
+    anti: bool,
+    seed: u32,
+}
+
+impl Buddhabrot {
+    pub fn new() -> Buddhabrot {
+        Buddhabrot { samples: 1_000_000, anti: false, seed: 0 }
+    }
+
+    /// The number of random `c` values sampled.
+    pub fn samples(&mut self, samples: u32) -> &mut Self {
+        self.samples = samples;
+        self
+    }
+
+    /// Counts the orbits that never escape instead of the ones that do.
+    pub fn anti(&mut self, anti: bool) -> &mut Self {
+        self.anti = anti;
+        self
+    }
+
+    /// The seed of the random `c` values, the same seed produces the same image.
+    pub fn seed(&mut self, seed: u32) -> &mut Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Returns the number of times the orbits went through every pixel, row by row.
+    pub fn density(
+        &self,
+        camera: &Camera,
+        dimensions: (u32, u32),
+        max_iterations: u32,
+    ) -> Vec<f64> {
+        let (width, height) = dimensions;
+        let len = (width * height) as usize;
+
+        // the camera is used backward, the world coordinates
+        // of the orbits are transformed into screen coordinates
+        let camera = Camera { screen_size: [width as f64, height as f64], ..*camera };
+        let to_screen = |z: Complex64| -> Option<usize> {
+            let [x, y] = camera.world_to_screen([z.re, z.im])?;
+            Some(y as usize * width as usize + x as usize)
+        };
+
+        (0..BATCHES)
+            .into_par_iter()
+            .map(|batch| {
+                let seed = [self.seed, batch, 0x9e37_79b9, 0x7f4a_7c15];
+                let mut rng = XorShiftRng::from_seed(seed);
+                let mut density = vec![0.0; len];
+                let mut orbit = Vec::with_capacity(max_iterations as usize);
+
+                let samples = self.samples / BATCHES + (batch < self.samples % BATCHES) as u32;
+                for _ in 0..samples {
+                    // the whole Mandelbrot fractal fits in this square
+                    let c = Complex64::new(rng.gen_range(-2.0, 2.0), rng.gen_range(-2.0, 2.0));
+
+                    // the orbits of these points never escape
+                    let inside = in_main_cardioid(c) || in_period2_bulb(c);
+                    if inside && !self.anti {
+                        continue;
+                    }
+
+                    // the sampled point itself is not part of the density,
+                    // it would only add a uniform noise
+                    orbit.clear();
+                    let mut z = c;
+                    while orbit.len() < max_iterations as usize && z.norm_sqr() <= 4.0 {
+                        z = z * z + c;
+                        orbit.push(z);
+                    }
+
+                    let escaped = z.norm_sqr() > 4.0;
+                    if escaped != self.anti {
+                        for index in orbit.iter().filter_map(|&z| to_screen(z)) {
+                            density[index] += 1.0;
+                        }
+                    }
+                }
+
+                density
+            })
+            .reduce(
+                || vec![0.0; len],
+                |mut a, b| {
+                    a.iter_mut().zip(b).for_each(|(a, b)| *a += b);
+                    a
+                },
+            )
+    }
+}
+
+impl Default for Buddhabrot {
+    fn default() -> Buddhabrot {
+        Buddhabrot::new()
+    }
+}
+
+/// Maps the densities in `[0, 1]` relatively to the densest pixels.
+fn tone_map(density: &[f64]) -> Vec<f64> {
+    let mut sorted: Vec<_> = density.iter().cloned().filter(|&d| d > 0.0).collect();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    let max = match sorted.get((sorted.len() as f64 * DENSITY_QUANTILE) as usize) {
+        Some(&max) => max,
+        None => match sorted.last() {
+            Some(&max) => max,
+            None => return vec![0.0; density.len()],
+        },
+    };
+
+    density.iter().map(|&d| (d / max).min(1.0)).collect()
+}
+
+/// Produces an image of the Buddhabrot, the densities are painted using the gradient.
+pub fn produce_buddhabrot_image(
+    buddhabrot: &Buddhabrot,
+    camera: &Camera,
+    dimensions: (u32, u32),
+    max_iterations: u32,
+    gradient: &Gradient<LinSrgb>,
+) -> RgbImage {
+    let (width, height) = dimensions;
+    let density = tone_map(&buddhabrot.density(camera, dimensions, max_iterations));
+
+    let mut image = RgbImage::new(width, height);
+    image.par_chunks_mut(3).zip(density.par_iter()).for_each(|(p, &d)| {
+        let data: [u8; 3] = gradient.get(d as f32).into_pixel();
+        p.copy_from_slice(&data);
+    });
+
+    image
+}
+
+/// Produces an image of the Nebulabrot, a Buddhabrot that computes the red,
+/// the green and the blue channels using different iteration limits.
+pub fn produce_nebulabrot_image(
+    buddhabrot: &Buddhabrot,
+    camera: &Camera,
+    dimensions: (u32, u32),
+    limits: [u32; 3],
+) -> RgbImage {
+    let (width, height) = dimensions;
+    let channels: Vec<_> = limits
+        .iter()
+        .map(|&limit| tone_map(&buddhabrot.density(camera, dimensions, limit)))
+        .collect();
+
+    let mut image = RgbImage::new(width, height);
+    image.par_chunks_mut(3).enumerate().for_each(|(i, p)| {
+        let [r, g, b] = [channels[0][i], channels[1][i], channels[2][i]];
+        let data: [u8; 3] = LinSrgb::new(r as f32, g as f32, b as f32).into_pixel();
+        p.copy_from_slice(&data);
+    });
+
+    image
+}
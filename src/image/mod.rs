@@ -1,9 +1,11 @@
 mod antialiazing;
+mod buddhabrot;
 mod complex_palette;
 mod screen_dimensions;
 mod sub_gradient;
 
 pub use self::antialiazing::Antialiazing;
+pub use self::buddhabrot::{produce_buddhabrot_image, produce_nebulabrot_image, Buddhabrot};
 pub use self::complex_palette::ComplexPalette;
 pub use self::screen_dimensions::ScreenDimensions;
 pub use self::sub_gradient::SubGradient;
@@ -12,7 +14,7 @@ use image::{imageops, FilterType, Rgb, RgbImage};
 use rayon::prelude::*;
 
 use crate::camera::Camera;
-use crate::fractal::Fractal;
+use crate::fractal::{Convergence, EscapeTime, Fractal, Lyapunov, Newton, SmoothIterations};
 
 pub fn edges(image: &RgbImage) -> RgbImage {
     let kernel = [-1.0, -1.0, -1.0, -1.0, 8.0, -1.0, -1.0, -1.0, -1.0];
@@ -28,7 +30,130 @@ pub fn produce_image<F, C>(
 ) -> RgbImage
 where
     F: Fractal + ?Sized + Sync,
-    C: Fn(u8) -> Rgb<u8> + Sync + Send,
+    C: Fn(EscapeTime) -> Rgb<u8> + Sync + Send,
+{
+    render_rows(camera, dimensions, antialiazing, |xs, ys, row| {
+        let mut escapes = vec![EscapeTime { iterations: 0, max_iterations: 0 }; xs.len()];
+        fractal.iterations_batch(xs, ys, &mut escapes);
+        paint_row(row, &escapes, &painter);
+    })
+}
+
+fn paint_row<C>(row: &mut [u8], escapes: &[EscapeTime], painter: &C)
+where
+    C: Fn(EscapeTime) -> Rgb<u8>,
+{
+    for (p, escape) in row.chunks_mut(3).zip(escapes) {
+        p.copy_from_slice(&painter(*escape).data);
+    }
+}
+
+/// Produces an image using the continuous escape values of the fractal,
+/// the painter can sample a gradient without visible color bands.
+pub fn produce_smooth_image<F, C>(
+    fractal: &F,
+    camera: &Camera,
+    dimensions: (u32, u32),
+    antialiazing: Option<u32>,
+    painter: C,
+) -> RgbImage
+where
+    F: Fractal + ?Sized + Sync,
+    C: Fn(SmoothIterations) -> Rgb<u8> + Sync + Send,
+{
+    render_image(camera, dimensions, antialiazing, |x, y| painter(fractal.smooth_iterations(x, y)))
+}
+
+/// Produces an image using the distance estimates of the fractal, the painter is given
+/// the distance to the fractal boundary in pixels, crisp filaments and outlines
+/// can be drawn from it where escape times miss them.
+///
+/// The painter is given `None` if the fractal does not support distance estimation.
+pub fn produce_distance_image<F, C>(
+    fractal: &F,
+    camera: &Camera,
+    dimensions: (u32, u32),
+    antialiazing: Option<u32>,
+    painter: C,
+) -> RgbImage
+where
+    F: Fractal + ?Sized + Sync,
+    C: Fn(Option<f64>) -> Rgb<u8> + Sync + Send,
+{
+    let pixel_size = pixel_size(camera, dimensions, antialiazing);
+    render_image(camera, dimensions, antialiazing, |x, y| {
+        painter(fractal.distance_estimate(x, y).map(|d| d / pixel_size))
+    })
+}
+
+/// Returns the size of a pixel in world coordinates, when the camera
+/// is rendered with the given dimensions and antialiazing.
+pub fn pixel_size(camera: &Camera, dimensions: (u32, u32), antialiazing: Option<u32>) -> f64 {
+    let (_, height) = dimensions;
+    let aa = antialiazing.unwrap_or(1);
+    2.0 * camera.zoom / (height * aa) as f64
+}
+
+/// Produces an image of a Newton fractal, the painter knows
+/// the root each point converged to and the time it took.
+pub fn produce_newton_image<C>(
+    fractal: &Newton,
+    camera: &Camera,
+    dimensions: (u32, u32),
+    antialiazing: Option<u32>,
+    painter: C,
+) -> RgbImage
+where
+    C: Fn(Convergence) -> Rgb<u8> + Sync + Send,
+{
+    render_image(camera, dimensions, antialiazing, |x, y| painter(fractal.convergence(x, y)))
+}
+
+/// Produces an image of a Lyapunov fractal, the painter is given
+/// the signed exponent of every point, negative when stable, positive when chaotic.
+pub fn produce_lyapunov_image<C>(
+    fractal: &Lyapunov,
+    camera: &Camera,
+    dimensions: (u32, u32),
+    antialiazing: Option<u32>,
+    painter: C,
+) -> RgbImage
+where
+    C: Fn(f64) -> Rgb<u8> + Sync + Send,
+{
+    render_image(camera, dimensions, antialiazing, |x, y| painter(fractal.exponent(x, y)))
+}
+
+/// Produces an image by calling the `pixel` function
+/// with the world coordinates of every pixel of the screen.
+pub fn render_image<P>(
+    camera: &Camera,
+    dimensions: (u32, u32),
+    antialiazing: Option<u32>,
+    pixel: P,
+) -> RgbImage
+where
+    P: Fn(f64, f64) -> Rgb<u8> + Sync + Send,
+{
+    // the world coordinates are rebuilt from the offsets
+    // to avoid rounding the high precision center for every pixel
+    let [cx, cy] = camera.center_f64();
+    render_offset_image(camera, dimensions, antialiazing, |x, y| pixel(cx + x, cy + y))
+}
+
+/// Produces an image by calling the `pixel` function with the offset
+/// of every pixel of the screen from the camera center, in world coordinates.
+///
+/// Offsets keep their precision at zooms where world coordinates can not,
+/// see `PerturbedMandelbrot`.
+pub fn render_offset_image<P>(
+    camera: &Camera,
+    dimensions: (u32, u32),
+    antialiazing: Option<u32>,
+    pixel: P,
+) -> RgbImage
+where
+    P: Fn(f64, f64) -> Rgb<u8> + Sync + Send,
 {
     assert!(antialiazing != Some(0), "antialiazing cannot be equal to zero, prefer 1 instead");
 
@@ -43,10 +168,9 @@ where
         let y = (i as u32 - x) / bwidth;
 
         let pos = [x as f64, y as f64];
-        let [x, y] = camera.screen_to_world(pos);
-        let i = fractal.iterations(x, y);
+        let [x, y] = camera.screen_to_offset(pos);
 
-        let data = painter(i).data;
+        let data = pixel(x, y).data;
         p.copy_from_slice(&data);
     });
 
@@ -56,3 +180,42 @@ where
         image
     }
 }
+
+/// Produces an image row by row, the `row` function is given the world coordinates
+/// of the pixels of a row and fills its RGB bytes, the coordinates are the same
+/// as the ones given by `render_image`.
+fn render_rows<R>(
+    camera: &Camera,
+    dimensions: (u32, u32),
+    antialiazing: Option<u32>,
+    row: R,
+) -> RgbImage
+where
+    R: Fn(&[f64], &[f64], &mut [u8]) + Sync + Send,
+{
+    assert!(antialiazing != Some(0), "antialiazing cannot be equal to zero, prefer 1 instead");
+
+    let (width, height) = dimensions;
+    let aa = antialiazing.unwrap_or(1) as f64;
+    let (bwidth, bheight) = (width * aa as u32, height * aa as u32);
+    let camera = Camera { screen_size: [bwidth as f64, bheight as f64], ..*camera };
+    let [cx, cy] = camera.center_f64();
+
+    let mut image = RgbImage::new(bwidth, bheight);
+    image.par_chunks_mut(3 * bwidth as usize).enumerate().for_each(|(y, pixels)| {
+        let mut xs = Vec::with_capacity(bwidth as usize);
+        let mut ys = Vec::with_capacity(bwidth as usize);
+        for x in 0..bwidth {
+            let [x, y] = camera.screen_to_offset([x as f64, y as f64]);
+            xs.push(cx + x);
+            ys.push(cy + y);
+        }
+        row(&xs, &ys, pixels);
+    });
+
+    if antialiazing.is_some() {
+        imageops::resize(&image, width, height, FilterType::Triangle)
+    } else {
+        image
+    }
+}
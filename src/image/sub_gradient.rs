@@ -13,6 +13,23 @@ impl SubGradient {
         SubGradient { from, to }
     }
 
+    /// The hand-picked ranges of constants that give detailed Julia sets, each one
+    /// goes a short way down a column of the parameter plane.
+    pub fn julia_presets() -> Vec<SubGradient> {
+        // https://upload.wikimedia.org/wikipedia/commons/a/a9/Julia-Teppich.png
+        // http://www.karlsims.com/julia.html
+        vec![
+            SubGradient::new(ComplexPalette::new(-0.8, 0.3), ComplexPalette::new(-0.8, 0.15)),
+            SubGradient::new(ComplexPalette::new(-0.6, 0.7), ComplexPalette::new(-0.6, 0.5)),
+            SubGradient::new(ComplexPalette::new(-0.4, 0.65), ComplexPalette::new(-0.4, 0.6)),
+            SubGradient::new(ComplexPalette::new(-0.2, 0.9), ComplexPalette::new(-0.2, 0.8)),
+            SubGradient::new(ComplexPalette::new(0.0, 1.0), ComplexPalette::new(0.0, 0.7)),
+            SubGradient::new(ComplexPalette::new(0.19, 0.6), ComplexPalette::new(0.19, 0.552)),
+            SubGradient::new(ComplexPalette::new(0.28, 0.01), ComplexPalette::new(0.28, -0.01)),
+            SubGradient::new(ComplexPalette::new(0.29, 0.6), ComplexPalette::new(0.29, 0.55)),
+        ]
+    }
+
     pub fn gradient(&self) -> Gradient<ComplexPalette> {
         Gradient::new(vec![self.from, self.to])
     }
@@ -1,8 +1,16 @@
+pub mod animation;
 pub mod camera;
 pub mod fractal;
+pub mod fractal3d;
 pub mod generate;
+pub mod ifs;
 pub mod image;
+pub mod lsystem;
+pub mod precision;
 
 pub use crate::camera::Camera;
 pub use crate::fractal::Fractal;
-pub use crate::fractal::{Julia, Mandelbrot};
+pub use crate::fractal::{
+    BurningShip, BurningShipJulia, Formula, Julia, Lyapunov, Magnet, Mandelbrot, Multibrot,
+    MultibrotJulia, Newton, Nova, NovaJulia, Phoenix, Tricorn, TricornJulia,
+};
@@ -0,0 +1,198 @@
+use std::fmt::Write;
+
+use image::{Rgb, RgbImage};
+use palette::rgb::LinSrgb;
+use palette::{Gradient, Mix};
+
+use crate::camera::Camera;
+use crate::lsystem::Line;
+use crate::precision::PrecisePoint;
+
+/// The space kept around the drawing when it is framed, relatively to its size.
+const MARGIN: f64 = 0.05;
+
+/// The lines drawn by a turtle, in world coordinates.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Drawing {
+    lines: Vec<Line>,
+}
+
+impl Drawing {
+    pub fn new(lines: Vec<Line>) -> Drawing {
+        Drawing { lines }
+    }
+
+    pub fn lines(&self) -> &[Line] {
+        &self.lines
+    }
+
+    /// Returns the top left and the bottom right corners of the drawing,
+    /// `None` if there is nothing drawn.
+    pub fn bounds(&self) -> Option<([f64; 2], [f64; 2])> {
+        let points = self.lines.iter().flat_map(|l| vec![l.start, l.end]);
+        points.fold(None, |bounds, [x, y]| match bounds {
+            None => Some(([x, y], [x, y])),
+            Some(([left, top], [right, bottom])) => {
+                Some(([left.min(x), top.min(y)], [right.max(x), bottom.max(y)]))
+            }
+        })
+    }
+
+    /// Moves and zooms the camera to frame the whole drawing.
+    pub fn frame(&self, camera: &mut Camera) {
+        let ([left, top], [right, bottom]) = match self.bounds() {
+            Some(bounds) => bounds,
+            None => return,
+        };
+
+        // the zoom is the half height of the view
+        let [sx, sy] = camera.screen_size;
+        let half_height = ((bottom - top) / 2.0).max((right - left) / 2.0 * sy / sx);
+
+        camera.center = PrecisePoint::from_f64([(left + right) / 2.0, (top + bottom) / 2.0]);
+        camera.zoom = (half_height * (1.0 + MARGIN)).max(1e-6);
+    }
+
+    /// Returns an SVG document of the drawing, consecutive lines are joined in the same path.
+    pub fn to_svg(&self, color: Rgb<u8>) -> String {
+        let ([left, top], [right, bottom]) = self.bounds().unwrap_or(([0.0; 2], [1.0; 2]));
+        let margin = (right - left).max(bottom - top).max(1e-6) * MARGIN;
+        let (width, height) = (right - left + 2.0 * margin, bottom - top + 2.0 * margin);
+
+        let mut path = String::new();
+        let mut last = None;
+        for line in &self.lines {
+            if last != Some(line.start) {
+                let _ = write!(path, "M{:.4} {:.4}", line.start[0], line.start[1]);
+            }
+            let _ = write!(path, "L{:.4} {:.4}", line.end[0], line.end[1]);
+            last = Some(line.end);
+        }
+
+        let [r, g, b] = color.data;
+        format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{:.4} {:.4} {:.4} {:.4}\">\
+             <path d=\"{}\" fill=\"none\" stroke=\"#{:02x}{:02x}{:02x}\" stroke-width=\"{:.4}\" \
+             stroke-linecap=\"round\" stroke-linejoin=\"round\"/></svg>\n",
+            left - margin,
+            top - margin,
+            width,
+            height,
+            path,
+            r,
+            g,
+            b,
+            width.max(height) / 500.0
+        )
+    }
+}
+
+/// Produces an image of the drawing with antialiased lines, the lines take
+/// the color of the gradient in the order they were drawn.
+pub fn produce_lsystem_image(
+    drawing: &Drawing,
+    camera: &Camera,
+    dimensions: (u32, u32),
+    gradient: &Gradient<LinSrgb>,
+    background: LinSrgb,
+) -> RgbImage {
+    let (width, height) = dimensions;
+    let len = (width * height) as usize;
+
+    // the pixel centers are on the integer coordinates
+    let camera = Camera { screen_size: [width as f64, height as f64], ..*camera };
+    let to_screen = |point: [f64; 2]| {
+        let [x, y] = camera.unclipped_world_to_screen(point);
+        [x - 0.5, y - 0.5]
+    };
+
+    // the colors are summed weighted by the coverage of the lines
+    let mut coverage = vec![0.0f32; len];
+    let mut colors = vec![LinSrgb::new(0.0, 0.0, 0.0); len];
+
+    let count = drawing.lines().len().max(2) - 1;
+    for (i, line) in drawing.lines().iter().enumerate() {
+        let color = gradient.get(i as f32 / count as f32);
+        let mut plot = |x: i64, y: i64, c: f64| {
+            if x >= 0 && x < width as i64 && y >= 0 && y < height as i64 {
+                let index = y as usize * width as usize + x as usize;
+                let c = c as f32;
+                coverage[index] += c;
+                let LinSrgb { red, green, blue, .. } = colors[index];
+                colors[index] = LinSrgb::new(
+                    red + color.red * c,
+                    green + color.green * c,
+                    blue + color.blue * c,
+                );
+            }
+        };
+        draw_line(to_screen(line.start), to_screen(line.end), &mut plot);
+    }
+
+    let mut image = RgbImage::new(width, height);
+    for (i, p) in image.pixels_mut().enumerate() {
+        let color = if coverage[i] > 0.0 {
+            let LinSrgb { red, green, blue, .. } = colors[i];
+            let c = coverage[i];
+            let average = LinSrgb::new(red / c, green / c, blue / c);
+            background.mix(&average, c.min(1.0))
+        } else {
+            background
+        };
+        *p = Rgb { data: color.into_pixel() };
+    }
+
+    image
+}
+
+/// Draws an antialiased line using the Xiaolin Wu algorithm,
+/// `plot` receives the pixels along with their coverage.
+fn draw_line<P>(start: [f64; 2], end: [f64; 2], plot: &mut P)
+where
+    P: FnMut(i64, i64, f64),
+{
+    let fpart = |v: f64| v - v.floor();
+    let rfpart = |v: f64| 1.0 - fpart(v);
+
+    let [mut x0, mut y0] = start;
+    let [mut x1, mut y1] = end;
+
+    // iterate along the longest axis
+    let steep = (y1 - y0).abs() > (x1 - x0).abs();
+    if steep {
+        std::mem::swap(&mut x0, &mut y0);
+        std::mem::swap(&mut x1, &mut y1);
+    }
+    if x0 > x1 {
+        std::mem::swap(&mut x0, &mut x1);
+        std::mem::swap(&mut y0, &mut y1);
+    }
+
+    let mut plot = |x: i64, y: i64, c: f64| if steep { plot(y, x, c) } else { plot(x, y, c) };
+
+    let dx = x1 - x0;
+    let gradient = if dx == 0.0 { 1.0 } else { (y1 - y0) / dx };
+
+    // the first endpoint
+    let x_end = x0.round();
+    let y_end = y0 + gradient * (x_end - x0);
+    let x_gap = rfpart(x0 + 0.5);
+    let x_start = x_end as i64;
+    plot(x_start, y_end.floor() as i64, rfpart(y_end) * x_gap);
+    plot(x_start, y_end.floor() as i64 + 1, fpart(y_end) * x_gap);
+    let mut inter_y = y_end + gradient;
+
+    // the second endpoint
+    let x_end = x1.round();
+    let y_end = y1 + gradient * (x_end - x1);
+    let x_gap = fpart(x1 + 0.5);
+    let x_stop = x_end as i64;
+    plot(x_stop, y_end.floor() as i64, rfpart(y_end) * x_gap);
+    plot(x_stop, y_end.floor() as i64 + 1, fpart(y_end) * x_gap);
+
+    for x in x_start + 1..x_stop {
+        plot(x, inter_y.floor() as i64, rfpart(inter_y));
+        plot(x, inter_y.floor() as i64 + 1, fpart(inter_y));
+        inter_y += gradient;
+    }
+}
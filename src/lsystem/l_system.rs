@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// A Lindenmayer system, every symbol of the axiom is replaced by its production rule
+/// at each iteration, the symbols without rule are kept.
+///
+/// The text format gives one setting per line, `#` starts a comment:
+///
+/// ```text
+/// # the Koch curve
+/// angle 60
+/// axiom F
+/// F = F+F--F+F
+/// ```
+///
+/// `heading` optionally gives the starting direction of the turtle in degrees.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LSystem {
+    axiom: String,
+    rules: HashMap<char, String>,
+    /// The angle of the turns in degrees.
+    angle: f64,
+    /// The starting direction of the turtle in degrees.
+    heading: f64,
+}
+
+impl LSystem {
+    pub fn new(axiom: &str, angle: f64) -> LSystem {
+        LSystem { axiom: axiom.to_string(), rules: HashMap::new(), angle, heading: 0.0 }
+    }
+
+    /// Adds the production rule of the symbol, replaces the previous one.
+    pub fn rule(&mut self, symbol: char, replacement: &str) -> &mut Self {
+        self.rules.insert(symbol, replacement.to_string());
+        self
+    }
+
+    pub fn heading(&mut self, heading: f64) -> &mut Self {
+        self.heading = heading;
+        self
+    }
+
+    pub fn axiom(&self) -> &str {
+        &self.axiom
+    }
+
+    pub fn angle(&self) -> f64 {
+        self.angle
+    }
+
+    pub fn starting_heading(&self) -> f64 {
+        self.heading
+    }
+
+    /// Applies the rules `depth` times to the axiom.
+    pub fn expand(&self, depth: u32) -> String {
+        let mut current = self.axiom.clone();
+        for _ in 0..depth {
+            let mut next = String::with_capacity(current.len() * 2);
+            for symbol in current.chars() {
+                match self.rules.get(&symbol) {
+                    Some(replacement) => next.push_str(replacement),
+                    None => next.push(symbol),
+                }
+            }
+            current = next;
+        }
+        current
+    }
+}
+
+impl FromStr for LSystem {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut axiom = None;
+        let mut angle = None;
+        let mut heading = 0.0;
+        let mut rules = HashMap::new();
+
+        for line in s.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(value) = line.strip_prefix("axiom") {
+                axiom = Some(value.trim().to_string());
+            } else if let Some(value) = line.strip_prefix("angle") {
+                angle = Some(value.trim().parse().map_err(|_| "invalid angle")?);
+            } else if let Some(value) = line.strip_prefix("heading") {
+                heading = value.trim().parse().map_err(|_| "invalid heading")?;
+            } else {
+                let mut parts = line.splitn(2, '=');
+                let symbol = parts.next().unwrap_or("").trim();
+                let replacement = parts.next().ok_or("expected a rule like \"F = F+F\"")?;
+
+                let mut chars = symbol.chars();
+                let symbol = match (chars.next(), chars.next()) {
+                    (Some(symbol), None) => symbol,
+                    _ => return Err("rules must replace a single symbol"),
+                };
+
+                let replacement: String =
+                    replacement.chars().filter(|c| !c.is_whitespace()).collect();
+                rules.insert(symbol, replacement);
+            }
+        }
+
+        let axiom = axiom.ok_or("missing axiom")?;
+        if axiom.is_empty() {
+            return Err("axiom can not be empty");
+        }
+        let angle = angle.ok_or("missing angle")?;
+
+        Ok(LSystem { axiom, rules, angle, heading })
+    }
+}
@@ -0,0 +1,9 @@
+mod drawing;
+mod l_system;
+mod preset;
+mod turtle;
+
+pub use self::drawing::{produce_lsystem_image, Drawing};
+pub use self::l_system::LSystem;
+pub use self::preset::Preset;
+pub use self::turtle::{Line, Turtle};
@@ -0,0 +1,104 @@
+use std::fmt;
+
+use rand_derive::Rand;
+
+use crate::lsystem::LSystem;
+
+const KOCH_CURVE: &str = "
+angle 60
+axiom F
+F = F-F++F-F
+";
+
+const DRAGON_CURVE: &str = "
+angle 90
+axiom FX
+X = X+YF+
+Y = -FX-Y
+";
+
+const HILBERT_CURVE: &str = "
+angle 90
+axiom A
+A = +BF-AFA-FB+
+B = -AF+BFB+FA-
+";
+
+const SIERPINSKI_TRIANGLE: &str = "
+angle 120
+axiom F-G-G
+F = F-G+F+G-F
+G = GG
+";
+
+/// The plants grow upward, the y axis goes down.
+const FRACTAL_PLANT: &str = "
+angle 25
+heading -90
+axiom X
+X = F+[[X]-X]-F[-FX]+X
+F = FF
+";
+
+const BUSH: &str = "
+angle 22.5
+heading -90
+axiom F
+F = FF+[+F-F-F]-[-F+F+F]
+";
+
+/// The built-in L-systems, most of them come from the book
+/// The Algorithmic Beauty of Plants.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Rand)]
+pub enum Preset {
+    KochCurve,
+    DragonCurve,
+    HilbertCurve,
+    SierpinskiTriangle,
+    FractalPlant,
+    Bush,
+}
+
+impl Preset {
+    /// The L-system in the text format.
+    pub fn source(&self) -> &'static str {
+        match *self {
+            Preset::KochCurve => KOCH_CURVE,
+            Preset::DragonCurve => DRAGON_CURVE,
+            Preset::HilbertCurve => HILBERT_CURVE,
+            Preset::SierpinskiTriangle => SIERPINSKI_TRIANGLE,
+            Preset::FractalPlant => FRACTAL_PLANT,
+            Preset::Bush => BUSH,
+        }
+    }
+
+    pub fn lsystem(&self) -> LSystem {
+        self.source().parse().expect("presets must be valid")
+    }
+
+    /// The depth at which the details are as thin as the lines of an image.
+    pub fn depth(&self) -> u32 {
+        match *self {
+            Preset::KochCurve => 5,
+            Preset::DragonCurve => 13,
+            Preset::HilbertCurve => 6,
+            Preset::SierpinskiTriangle => 7,
+            Preset::FractalPlant => 6,
+            Preset::Bush => 4,
+        }
+    }
+}
+
+impl fmt::Display for Preset {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match *self {
+            Preset::KochCurve => "koch curve",
+            Preset::DragonCurve => "dragon curve",
+            Preset::HilbertCurve => "hilbert curve",
+            Preset::SierpinskiTriangle => "sierpinski triangle",
+            Preset::FractalPlant => "fractal plant",
+            Preset::Bush => "bush",
+        };
+        f.write_str(name)
+    }
+}
@@ -0,0 +1,79 @@
+use crate::lsystem::LSystem;
+
+/// A segment drawn by the turtle.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Line {
+    pub start: [f64; 2],
+    pub end: [f64; 2],
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct State {
+    position: [f64; 2],
+    /// The direction in radians.
+    heading: f64,
+}
+
+/// Interprets the symbols of an L-system as drawing commands:
+///
+///   - `F` and `G` move forward drawing a line
+///   - `f` moves forward without drawing
+///   - `+` and `-` turn by the angle, `|` turns around
+///   - `[` saves the position and the direction, `]` restores them
+///
+/// The other symbols are ignored. The y axis goes down, like on screen,
+/// so positive angles turn clockwise.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Turtle {
+    /// The angle of the turns in degrees.
+    angle: f64,
+    step: f64,
+    /// The starting direction in degrees.
+    heading: f64,
+}
+
+impl Turtle {
+    pub fn new(angle: f64, step: f64) -> Turtle {
+        Turtle { angle, step, heading: 0.0 }
+    }
+
+    /// The turtle that draws the L-system using its angle and heading.
+    pub fn for_lsystem(lsystem: &LSystem, step: f64) -> Turtle {
+        Turtle { angle: lsystem.angle(), step, heading: lsystem.starting_heading() }
+    }
+
+    pub fn heading(&mut self, heading: f64) -> &mut Self {
+        self.heading = heading;
+        self
+    }
+
+    /// Returns the lines drawn by the commands, in the order they are drawn.
+    pub fn draw(&self, commands: &str) -> Vec<Line> {
+        let angle = self.angle.to_radians();
+        let mut state = State { position: [0.0, 0.0], heading: self.heading.to_radians() };
+        let mut stack = Vec::new();
+        let mut lines = Vec::new();
+
+        for command in commands.chars() {
+            match command {
+                'F' | 'G' | 'f' => {
+                    let [x, y] = state.position;
+                    let (sin, cos) = state.heading.sin_cos();
+                    let end = [x + cos * self.step, y + sin * self.step];
+                    if command != 'f' {
+                        lines.push(Line { start: state.position, end });
+                    }
+                    state.position = end;
+                }
+                '+' => state.heading += angle,
+                '-' => state.heading -= angle,
+                '|' => state.heading += std::f64::consts::PI,
+                '[' => stack.push(state),
+                ']' => state = stack.pop().unwrap_or(state),
+                _ => (),
+            }
+        }
+
+        lines
+    }
+}
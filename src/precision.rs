@@ -0,0 +1,372 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::{Add, Mul, Neg, Sub};
+use std::str::FromStr;
+
+/// The number of 32 bits limbs of a `BigFixed`, the first one holds the integer part.
+const LIMBS: usize = 8;
+
+/// The number of bits after the point, the resolution is about `1e-67`.
+const FRACTION_BITS: i32 = 32 * (LIMBS as i32 - 1);
+
+/// A signed fixed-point number with a 32 bits integer part and a 224 bits fraction,
+/// precise enough to keep track of a camera center while zooming far beyond
+/// what a `f64` can distinguish.
+///
+/// This is not an arbitrary precision number: the digits after about `1e-67` are lost,
+/// zooms much deeper than `1e-60` are not precise anymore, and the numbers saturate
+/// to the largest magnitude instead of overflowing past `2^32`.
+#[derive(Copy, Clone, Eq)]
+pub struct BigFixed {
+    negative: bool,
+    /// The magnitude, most significant limb first.
+    limbs: [u32; LIMBS],
+}
+
+impl BigFixed {
+    pub fn zero() -> BigFixed {
+        BigFixed { negative: false, limbs: [0; LIMBS] }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.iter().all(|&l| l == 0)
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative && !self.is_zero()
+    }
+
+    /// The number with the largest magnitude, the positive numbers saturate to it.
+    pub fn max_value() -> BigFixed {
+        BigFixed { negative: false, limbs: [u32::MAX; LIMBS] }
+    }
+
+    /// Converts a float without losing any of its bits when its integer part
+    /// fits in 32 bits, the larger floats saturate to the largest magnitude
+    /// and `NaN` converts to zero.
+    pub fn from_f64(value: f64) -> BigFixed {
+        if value.is_nan() || value == 0.0 {
+            return BigFixed::zero();
+        }
+        if value.abs() >= 4294967296.0 {
+            return BigFixed { negative: value < 0.0, ..BigFixed::max_value() };
+        }
+
+        let bits = value.to_bits();
+        let exponent = ((bits >> 52) & 0x7ff) as i32;
+        let mantissa = bits & ((1 << 52) - 1);
+        let (mantissa, exponent) =
+            if exponent == 0 { (mantissa, -1074) } else { (mantissa | (1 << 52), exponent - 1075) };
+
+        // the value is `mantissa * 2^exponent`, every bit of the mantissa
+        // is placed at its position counted from the least significant bit
+        let mut limbs = [0; LIMBS];
+        let shift = exponent + FRACTION_BITS;
+        for bit in 0..53 {
+            if mantissa & (1 << bit) == 0 {
+                continue;
+            }
+            let position = bit + shift;
+            if position < 0 {
+                continue;
+            }
+            let limb = LIMBS - 1 - (position / 32) as usize;
+            limbs[limb] |= 1 << (position % 32);
+        }
+
+        BigFixed { negative: value < 0.0, limbs }
+    }
+
+    /// Returns the nearest float, the bits that a `f64` can not hold are lost.
+    pub fn to_f64(&self) -> f64 {
+        let mut value = 0.0;
+        let mut scale = 1.0;
+        for &limb in self.limbs.iter() {
+            value += limb as f64 * scale;
+            scale /= 4294967296.0;
+        }
+        if self.negative {
+            -value
+        } else {
+            value
+        }
+    }
+
+    fn cmp_magnitude(&self, other: &BigFixed) -> Ordering {
+        self.limbs.cmp(&other.limbs)
+    }
+
+    /// Adds the magnitudes, saturates to the largest magnitude on overflow.
+    fn add_magnitude(a: &[u32; LIMBS], b: &[u32; LIMBS]) -> [u32; LIMBS] {
+        let mut limbs = [0; LIMBS];
+        let mut carry = 0u64;
+        for i in (0..LIMBS).rev() {
+            let sum = a[i] as u64 + b[i] as u64 + carry;
+            limbs[i] = sum as u32;
+            carry = sum >> 32;
+        }
+        if carry != 0 {
+            return [u32::MAX; LIMBS];
+        }
+        limbs
+    }
+
+    /// Subtracts the magnitudes, `a` must be greater than or equal to `b`.
+    fn sub_magnitude(a: &[u32; LIMBS], b: &[u32; LIMBS]) -> [u32; LIMBS] {
+        let mut limbs = [0; LIMBS];
+        let mut borrow = 0i64;
+        for i in (0..LIMBS).rev() {
+            let mut diff = a[i] as i64 - b[i] as i64 - borrow;
+            borrow = 0;
+            if diff < 0 {
+                diff += 1 << 32;
+                borrow = 1;
+            }
+            limbs[i] = diff as u32;
+        }
+        limbs
+    }
+
+    /// Divides the magnitude by a small number, returns the remainder.
+    fn div_small(&mut self, divisor: u32) -> u32 {
+        let mut remainder = 0u64;
+        for limb in self.limbs.iter_mut() {
+            let current = (remainder << 32) | *limb as u64;
+            *limb = (current / divisor as u64) as u32;
+            remainder = current % divisor as u64;
+        }
+        remainder as u32
+    }
+
+    /// Multiplies the fraction by a small number, returns what overflowed into the integer part.
+    fn mul_small_fraction(&mut self, factor: u32) -> u32 {
+        let mut carry = 0u64;
+        for limb in self.limbs[1..].iter_mut().rev() {
+            let product = *limb as u64 * factor as u64 + carry;
+            *limb = product as u32;
+            carry = product >> 32;
+        }
+        carry as u32
+    }
+}
+
+impl Default for BigFixed {
+    fn default() -> BigFixed {
+        BigFixed::zero()
+    }
+}
+
+// the sign of zero is ignored, a difference that cancels exactly gives a negative zero
+impl PartialEq for BigFixed {
+    fn eq(&self, other: &BigFixed) -> bool {
+        self.is_negative() == other.is_negative() && self.limbs == other.limbs
+    }
+}
+
+impl Hash for BigFixed {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.is_negative().hash(state);
+        self.limbs.hash(state);
+    }
+}
+
+impl Neg for BigFixed {
+    type Output = BigFixed;
+
+    fn neg(self) -> BigFixed {
+        BigFixed { negative: !self.negative, ..self }
+    }
+}
+
+impl Add for BigFixed {
+    type Output = BigFixed;
+
+    fn add(self, other: BigFixed) -> BigFixed {
+        if self.negative == other.negative {
+            let limbs = BigFixed::add_magnitude(&self.limbs, &other.limbs);
+            return BigFixed { negative: self.negative, limbs };
+        }
+
+        match self.cmp_magnitude(&other) {
+            Ordering::Less => {
+                let limbs = BigFixed::sub_magnitude(&other.limbs, &self.limbs);
+                BigFixed { negative: other.negative, limbs }
+            }
+            _ => {
+                let limbs = BigFixed::sub_magnitude(&self.limbs, &other.limbs);
+                BigFixed { negative: self.negative, limbs }
+            }
+        }
+    }
+}
+
+impl Sub for BigFixed {
+    type Output = BigFixed;
+
+    fn sub(self, other: BigFixed) -> BigFixed {
+        self + -other
+    }
+}
+
+impl Mul for BigFixed {
+    type Output = BigFixed;
+
+    fn mul(self, other: BigFixed) -> BigFixed {
+        // schoolbook multiplication, least significant limb first
+        let mut product = [0u64; 2 * LIMBS];
+        for i in 0..LIMBS {
+            let a = self.limbs[LIMBS - 1 - i] as u64;
+            let mut carry = 0u64;
+            for j in 0..LIMBS {
+                let b = other.limbs[LIMBS - 1 - j] as u64;
+                let current = product[i + j] + a * b + carry;
+                product[i + j] = current & 0xffff_ffff;
+                carry = current >> 32;
+            }
+            product[i + LIMBS] += carry;
+        }
+
+        // the product has twice the fraction bits, drop the lowest ones
+        let negative = self.negative != other.negative;
+        if product[2 * LIMBS - 1] != 0 {
+            return BigFixed { negative, ..BigFixed::max_value() };
+        }
+        let mut limbs = [0; LIMBS];
+        for (k, limb) in limbs.iter_mut().enumerate() {
+            *limb = product[2 * LIMBS - 2 - k] as u32;
+        }
+
+        BigFixed { negative, limbs }
+    }
+}
+
+impl FromStr for BigFixed {
+    type Err = &'static str;
+
+    /// Parses a decimal number like `-0.743643887037158704752191506114774`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (negative, s) = match s.as_bytes().first() {
+            Some(b'-') => (true, &s[1..]),
+            Some(b'+') => (false, &s[1..]),
+            _ => (false, s),
+        };
+
+        let mut splitted = s.splitn(2, '.');
+        let integer = splitted.next().unwrap_or("");
+        let fraction = splitted.next().unwrap_or("");
+
+        if integer.is_empty() && fraction.is_empty() {
+            return Err("invalid number");
+        }
+        if !fraction.bytes().all(|b| b.is_ascii_digit()) {
+            return Err("invalid fraction digits");
+        }
+
+        let integer = if integer.is_empty() {
+            0
+        } else {
+            integer.parse::<u32>().map_err(|_| "invalid integer part")?
+        };
+
+        // the digits are read from the least significant one,
+        // each of them is added then the whole fraction is shifted by dividing by ten
+        let mut number = BigFixed::zero();
+        for digit in fraction.bytes().rev() {
+            number.limbs[0] = (digit - b'0') as u32;
+            number.div_small(10);
+        }
+        number.limbs[0] = integer;
+        number.negative = negative;
+
+        Ok(number)
+    }
+}
+
+impl fmt::Display for BigFixed {
+    /// Writes the decimal digits of the number, the precision of the formatter
+    /// is the number of fraction digits, all the significant ones are written otherwise.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // 224 bits of fraction are exactly represented by 224 decimal digits,
+        // but only the first 67 ones are significant
+        let significant = (FRACTION_BITS as f64 * 2f64.log10()) as usize;
+        let digits_count = f.precision().unwrap_or(significant);
+
+        // round the last digit written by adding half of its unit
+        let mut half = BigFixed::zero();
+        half.limbs[0] = 5;
+        for _ in 0..=digits_count {
+            half.div_small(10);
+        }
+        let rounded = BigFixed::add_magnitude(&self.limbs, &half.limbs);
+
+        let mut fraction = BigFixed { negative: self.negative, limbs: rounded };
+        let mut digits = String::with_capacity(digits_count);
+        for _ in 0..digits_count {
+            let digit = fraction.mul_small_fraction(10);
+            digits.push((b'0' + digit as u8) as char);
+        }
+
+        if f.precision().is_none() {
+            let trimmed = digits.trim_end_matches('0').len();
+            digits.truncate(trimmed.max(1));
+        }
+
+        let sign = if self.is_negative() { "-" } else { "" };
+        if digits.is_empty() {
+            write!(f, "{}{}", sign, fraction.limbs[0])
+        } else {
+            write!(f, "{}{}.{}", sign, fraction.limbs[0], digits)
+        }
+    }
+}
+
+impl fmt::Debug for BigFixed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "BigFixed({})", self)
+    }
+}
+
+/// A point of the world with high precision coordinates, see `BigFixed` for their limits.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct PrecisePoint {
+    pub x: BigFixed,
+    pub y: BigFixed,
+}
+
+impl PrecisePoint {
+    pub fn new(x: BigFixed, y: BigFixed) -> PrecisePoint {
+        PrecisePoint { x, y }
+    }
+
+    pub fn from_f64(point: [f64; 2]) -> PrecisePoint {
+        let [x, y] = point;
+        PrecisePoint { x: BigFixed::from_f64(x), y: BigFixed::from_f64(y) }
+    }
+
+    pub fn to_f64(&self) -> [f64; 2] {
+        [self.x.to_f64(), self.y.to_f64()]
+    }
+
+    /// Returns the point moved by a small offset, the offset itself can be
+    /// a low precision float without loosing the precision of the point.
+    pub fn offset(&self, offset: [f64; 2]) -> PrecisePoint {
+        let [dx, dy] = offset;
+        PrecisePoint { x: self.x + BigFixed::from_f64(dx), y: self.y + BigFixed::from_f64(dy) }
+    }
+
+    /// Returns the offset that leads from the `origin` to this point.
+    pub fn offset_from(&self, origin: &PrecisePoint) -> [f64; 2] {
+        [(self.x - origin.x).to_f64(), (self.y - origin.y).to_f64()]
+    }
+}
+
+impl fmt::Display for PrecisePoint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match f.precision() {
+            Some(precision) => write!(f, "({:.*}, {:.*})", precision, self.x, precision, self.y),
+            None => write!(f, "({}, {})", self.x, self.y),
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use frustalz::generate::{DateSeed, Generator};
+use frustalz::image::{Antialiazing, ScreenDimensions};
+use rand::{SeedableRng, StdRng};
+
+/// Generates the legacy image of the date seeded the same way as the binaries.
+fn generate(date: &str) -> image::RgbImage {
+    let datetime: DateSeed = date.parse().unwrap();
+    let mut s = DefaultHasher::new();
+    datetime.hash(&mut s);
+    let rng = StdRng::from_seed(&[s.finish() as usize]);
+
+    let mut generator = Generator::new(rng);
+    generator
+        .dive_dimensions(ScreenDimensions(100, 100))
+        .shot_dimensions(ScreenDimensions(150, 100))
+        .antialiazing(Antialiazing::new(1).unwrap())
+        .debug_images(false)
+        .legacy_bailout(true);
+
+    let (_, image) = generator.generate();
+    image
+}
+
+/// Whether the image is the same as the reference generated by the first versions
+/// with the same seed and dimensions.
+fn same_as_reference(image: image::RgbImage, name: &str) -> bool {
+    let path = format!("{}/tests/legacy/{}.png", env!("CARGO_MANIFEST_DIR"), name);
+    let reference = image::open(path).unwrap().to_rgb();
+    image.dimensions() == reference.dimensions() && image.into_raw() == reference.into_raw()
+}
+
+#[test]
+fn legacy_julia() {
+    let image = generate("2020-01-03T05:00:00Z");
+    assert!(same_as_reference(image, "julia-2020-01-03T05"), "the legacy Julia image changed");
+}
+
+#[test]
+fn legacy_mandelbrot() {
+    let image = generate("2020-01-17T03:00:00Z");
+    assert!(
+        same_as_reference(image, "mandelbrot-2020-01-17T03"),
+        "the legacy Mandelbrot image changed"
+    );
+}